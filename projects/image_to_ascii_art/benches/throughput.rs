@@ -0,0 +1,68 @@
+//! throughput of the renderers, in megapixels of source image per second
+//!
+//! run with `cargo bench`, or `cargo bench --features parallel` to compare against the parallel renderer
+
+use image_to_ascii_art::{AsciiRenderer, Dither, Mode};
+use std::time::Instant;
+
+/// a busy test pattern, so dithering and edge detection have something to work on
+fn pattern(width: u32, height: u32) -> image::RgbImage {
+    image::RgbImage::from_fn(width, height, |x, y| {
+        let v = ((x ^ y) % 256) as u8;
+        image::Rgb([v, (x * 255 / width) as u8, (y * 255 / height) as u8])
+    })
+}
+
+/// runs f a few times and prints the best time as throughput over `pixels` pixels
+fn bench<F: FnMut()>(name: &str, pixels: u64, mut f: F) {
+    const RUNS: usize = 5;
+    let mut best = f64::MAX;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        f();
+        best = best.min(start.elapsed().as_secs_f64());
+    }
+    println!("{:<45} {:>10.3} s {:>10.1} MP/s", name, best, pixels as f64 / best / 1_000_000.0);
+}
+
+fn main() {
+    let (width, height) = (2048, 2048);
+    let pixels = (width * height) as u64;
+    let img = image::DynamicImage::ImageRgb8(pattern(width, height));
+
+    //the render step on its own, at a size where it's the bottleneck
+    let big = AsciiRenderer::new().width(1024).cell_aspect(1.0);
+    for (name, renderer) in [
+        ("blocks", big.clone()),
+        ("blocks, bayer dither", big.clone().dither(Dither::Bayer(4))),
+        ("blocks, floyd-steinberg dither", big.clone().dither(Dither::FloydSteinberg)),
+        ("edges", big.clone().mode(Mode::Edges)),
+        ("braille", big.clone().mode(Mode::Braille)),
+    ]
+    .iter()
+    {
+        let resized = renderer.resize(&img);
+        let resized_pixels = (resized.width() * resized.height()) as u64;
+        bench(&format!("render_resized {}", name), resized_pixels, || {
+            renderer.render_resized(&resized);
+        });
+    }
+
+    //resizing and rendering together, at terminal size
+    let renderer = AsciiRenderer::new().width(120);
+    bench("render 2048x2048 to 120 columns", pixels, || {
+        renderer.render(&img);
+    });
+
+    //a file over the streaming threshold, decoded row by row
+    let (width, height) = (4200, 4200);
+    let path = std::env::temp_dir().join("image_to_ascii_art_throughput.png");
+    pattern(width, height).save(&path).unwrap();
+    bench("render_file 4200x4200 png (streamed)", (width * height) as u64, || {
+        renderer.render_file(&path).unwrap();
+    });
+    bench("open + render 4200x4200 png (whole)", (width * height) as u64, || {
+        renderer.render(&image::open(&path).unwrap());
+    });
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,173 @@
+//! animated images, decodes every frame of an animated GIF or APNG so they can be rendered one by one,
+//! played back in the terminal, or saved as text files or an asciicast v2 recording
+//!
+//! video files (mp4, webm, avi, ...) aren't supported, there's no video decoder in the image crate.
+//! convert them to a GIF or APNG first, e.g. `ffmpeg -i clip.mp4 -vf fps=10,scale=160:-1 clip.gif`
+
+use image::{AnimationDecoder, ImageFormat, ImageResult};
+use std::fs;
+use std::io::{self, Cursor, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+/// moves the cursor to the top left corner of the terminal
+const CURSOR_HOME: &str = "\x1b[H";
+/// clears the whole terminal
+const CLEAR_SCREEN: &str = "\x1b[2J";
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+
+/// how long frames with no delay of their own stay on screen, browsers do the same for 0 delay GIFs
+const DEFAULT_DELAY: Duration = Duration::from_millis(100);
+
+/// one rendered frame of an animation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub art: String,
+    /// how long the frame stays on screen before the next one is drawn
+    pub delay: Duration,
+}
+
+/**
+ * reads every frame of an animated GIF or APNG, any other image is read as a single frame
+ */
+pub fn load_frames(path: &str) -> ImageResult<Vec<image::Frame>> {
+    frames_from_memory(&fs::read(path)?)
+}
+
+/**
+ * decodes every frame of an animated GIF or APNG held in memory, any other image is decoded as a single frame
+ */
+pub fn frames_from_memory(bytes: &[u8]) -> ImageResult<Vec<image::Frame>> {
+    if is_video(bytes) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "video files aren't supported, convert it to a GIF or APNG first").into());
+    }
+    match image::guess_format(bytes)? {
+        ImageFormat::Gif => image::codecs::gif::GifDecoder::new(Cursor::new(bytes))?.into_frames().collect_frames(),
+        ImageFormat::Png => {
+            let decoder = image::codecs::png::PngDecoder::new(Cursor::new(bytes))?;
+            if decoder.is_apng() {
+                decoder.apng().into_frames().collect_frames()
+            } else {
+                Ok(vec![image::Frame::new(image::load_from_memory(bytes)?.into_rgba8())])
+            }
+        }
+        _ => Ok(vec![image::Frame::new(image::load_from_memory(bytes)?.into_rgba8())]),
+    }
+}
+
+/**
+ * whether the bytes start like one of the common video containers: mp4/mov, webm/mkv or avi
+ */
+fn is_video(bytes: &[u8]) -> bool {
+    let mp4 = bytes.len() >= 8 && &bytes[4..8] == b"ftyp";
+    let matroska = bytes.starts_with(&[0x1a, 0x45, 0xdf, 0xa3]);
+    let avi = bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"AVI ";
+    mp4 || matroska || avi
+}
+
+/**
+ * how long a decoded frame should stay on screen
+ */
+pub fn frame_delay(frame: &image::Frame) -> Duration {
+    let (numerator, denominator) = frame.delay().numer_denom_ms();
+    if numerator == 0 || denominator == 0 {
+        return DEFAULT_DELAY;
+    }
+    Duration::from_micros(numerator as u64 * 1000 / denominator as u64)
+}
+
+/// hides the cursor while it's alive, and shows it again when dropped, however playback ends
+struct HiddenCursor<'a, W: Write> {
+    out: &'a mut W,
+}
+impl<'a, W: Write> HiddenCursor<'a, W> {
+    fn new(out: &'a mut W) -> io::Result<HiddenCursor<'a, W>> {
+        write!(out, "{}", HIDE_CURSOR)?;
+        Ok(HiddenCursor { out })
+    }
+}
+impl<W: Write> Drop for HiddenCursor<'_, W> {
+    fn drop(&mut self) {
+        let _ = write!(self.out, "{}", SHOW_CURSOR);
+        let _ = self.out.flush();
+    }
+}
+
+/**
+ * plays the frames in the terminal, drawing every frame over the last one
+ *
+ * the animation is played `loops` times, or forever if `loops` is 0, it stops early once `stop` is set
+ * (by a Ctrl-C handler for example), the cursor is shown again however it stops
+ */
+pub fn play<W: Write>(frames: &[Frame], loops: u32, stop: &AtomicBool, out: &mut W) -> io::Result<()> {
+    let cursor = HiddenCursor::new(out)?;
+    write!(cursor.out, "{}", CLEAR_SCREEN)?;
+    let mut played = 0;
+    while loops == 0 || played < loops {
+        for frame in frames {
+            if stop.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+            write!(cursor.out, "{}{}", CURSOR_HOME, frame.art)?;
+            cursor.out.flush()?;
+            thread::sleep(frame.delay);
+        }
+        played += 1;
+    }
+    Ok(())
+}
+
+/**
+ * saves every frame as its own text file in `dir`, named frame_0000.txt, frame_0001.txt, ...
+ */
+pub fn write_text_frames(frames: &[Frame], dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    for (index, frame) in frames.iter().enumerate() {
+        fs::write(dir.join(format!("frame_{:04}.txt", index)), &frame.art)?;
+    }
+    Ok(())
+}
+
+/**
+ * writes the frames as an asciicast v2 recording, which can be played with `asciinema play`
+ *
+ * the recording is a JSON header line followed by one `[time, "o", text]` event per frame
+ */
+pub fn write_asciicast<W: Write>(frames: &[Frame], out: &mut W) -> io::Result<()> {
+    let width = frames.iter().flat_map(|f| f.art.lines()).map(|l| l.chars().count()).max().unwrap_or(0);
+    let height = frames.iter().map(|f| f.art.lines().count()).max().unwrap_or(0);
+    writeln!(out, "{{\"version\": 2, \"width\": {}, \"height\": {}}}", width, height)?;
+
+    let mut time = Duration::from_secs(0);
+    for (index, frame) in frames.iter().enumerate() {
+        //terminals need a carriage return as well as a new line to get back to the start of the line
+        let mut text = String::from(if index == 0 {CLEAR_SCREEN} else {""});
+        text.push_str(CURSOR_HOME);
+        text.push_str(&frame.art.replace('\n', "\r\n"));
+        writeln!(out, "[{:.6}, \"o\", \"{}\"]", time.as_secs_f64(), json_escape(&text))?;
+        time += frame.delay;
+    }
+    Ok(())
+}
+
+/**
+ * escapes a string so it can be put between quotes in JSON
+ */
+pub(crate) fn json_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,237 @@
+//! batch conversion, rendering every image in a directory or matching a glob into a tree of output files
+
+use crate::animation::json_escape;
+use crate::AsciiRenderer;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// the file format every image is saved in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// plain text
+    #[default]
+    Text,
+    /// text with 24 bit ANSI color codes
+    Ansi,
+    Html,
+    Svg,
+}
+impl OutputFormat {
+    /// the extension given to output files
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Text => "txt",
+            OutputFormat::Ansi => "ans",
+            OutputFormat::Html => "html",
+            OutputFormat::Svg => "svg",
+        }
+    }
+}
+impl FromStr for OutputFormat {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "txt" | "text" => Ok(OutputFormat::Text),
+            "ansi" | "ans" => Ok(OutputFormat::Ansi),
+            "html" => Ok(OutputFormat::Html),
+            "svg" => Ok(OutputFormat::Svg),
+            _ => Err("unknown output format, expected one of: txt, ansi, html, svg"),
+        }
+    }
+}
+
+/// what happened to one input image
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchEntry {
+    pub input: PathBuf,
+    /// where the art was saved, None if it failed
+    pub output: Option<PathBuf>,
+    /// why it failed, None if it was converted
+    pub error: Option<String>,
+}
+
+/// what happened to every input image, in the order they were converted
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BatchReport {
+    pub entries: Vec<BatchEntry>,
+}
+impl BatchReport {
+    pub fn converted(&self) -> usize {
+        self.entries.iter().filter(|e| e.error.is_none()).count()
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &BatchEntry> {
+        self.entries.iter().filter(|e| e.error.is_some())
+    }
+
+    /**
+     * the report as a JSON manifest, listing every input with its output or error
+     */
+    pub fn to_json(&self) -> String {
+        let path = |p: &Path| format!("\"{}\"", json_escape(&p.to_string_lossy()));
+        let mut json = String::from("{\n");
+        json.push_str(&format!("  \"total\": {},\n", self.entries.len()));
+        json.push_str(&format!("  \"converted\": {},\n", self.converted()));
+        json.push_str(&format!("  \"failed\": {},\n", self.entries.len() - self.converted()));
+        json.push_str("  \"files\": [");
+        for (index, entry) in self.entries.iter().enumerate() {
+            json.push_str(if index == 0 {"\n"} else {",\n"});
+            json.push_str(&format!("    {{\"input\": {}, ", path(&entry.input)));
+            match (&entry.output, &entry.error) {
+                (_, Some(error)) => json.push_str(&format!("\"output\": null, \"error\": \"{}\"}}", json_escape(error))),
+                (Some(output), None) => json.push_str(&format!("\"output\": {}, \"error\": null}}", path(output))),
+                (None, None) => json.push_str("\"output\": null, \"error\": null}"),
+            }
+        }
+        json.push_str(if self.entries.is_empty() {"]\n}\n"} else {"\n  ]\n}\n"});
+        json
+    }
+}
+impl fmt::Display for BatchReport {
+    /**
+     * a summary of the batch, with every failure and why it failed
+     */
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let failed = self.entries.len() - self.converted();
+        writeln!(f, "converted {} of {} images, {} failed", self.converted(), self.entries.len(), failed)?;
+        for entry in self.failed() {
+            writeln!(f, "  {}: {}", entry.input.display(), entry.error.as_deref().unwrap_or_default())?;
+        }
+        Ok(())
+    }
+}
+
+/**
+ * whether a path is a batch of images rather than a single one, a directory or a glob pattern
+ */
+pub fn is_batch(pattern: &str) -> bool {
+    Path::new(pattern).is_dir() || pattern.contains(['*', '?', '['])
+}
+
+/**
+ * whether a file has the extension of an image format that can be decoded
+ */
+pub fn is_supported_image(path: &Path) -> bool {
+    image::ImageFormat::from_path(path).is_ok()
+}
+
+/// the images of a batch, found by `collect_inputs`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inputs {
+    /// the directory output paths are made relative to
+    pub base: PathBuf,
+    /// every image found, in sorted order
+    pub images: Vec<PathBuf>,
+    /// a failed entry for every directory that couldn't be read, the rest of the batch carries on without them
+    pub unreadable: Vec<BatchEntry>,
+}
+
+/**
+ * finds every supported image in a directory (and its subdirectories), or matching a glob pattern
+ */
+pub fn collect_inputs(pattern: &str) -> Result<Inputs, Box<dyn Error>> {
+    let mut inputs = Vec::new();
+    let mut unreadable = Vec::new();
+    let base;
+    if Path::new(pattern).is_dir() {
+        base = PathBuf::from(pattern);
+        walk(&base, &mut inputs, &mut unreadable);
+    } else {
+        //the base is everything before the first component with a wildcard in it
+        base = Path::new(pattern)
+            .components()
+            .take_while(|c| !c.as_os_str().to_string_lossy().contains(['*', '?', '[']))
+            .collect();
+        for entry in glob::glob(pattern)? {
+            match entry {
+                Ok(path) if path.is_file() && is_supported_image(&path) => inputs.push(path),
+                Ok(_) => {}
+                Err(e) => unreadable.push(failed(e.path(), e.error())),
+            }
+        }
+    }
+    inputs.sort();
+    Ok(Inputs { base, images: inputs, unreadable })
+}
+
+/**
+ * adds every supported image under dir to inputs, and every directory that can't be read to unreadable
+ */
+pub(crate) fn walk(dir: &Path, inputs: &mut Vec<PathBuf>, unreadable: &mut Vec<BatchEntry>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => return unreadable.push(failed(dir, &e)),
+    };
+    for entry in entries {
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            Err(e) => {
+                unreadable.push(failed(dir, &e));
+                continue;
+            }
+        };
+        if path.is_dir() {
+            walk(&path, inputs, unreadable);
+        } else if is_supported_image(&path) {
+            inputs.push(path);
+        }
+    }
+}
+
+/**
+ * the entry of an input that failed
+ */
+fn failed(input: &Path, error: &dyn fmt::Display) -> BatchEntry {
+    BatchEntry { input: input.to_path_buf(), output: None, error: Some(error.to_string()) }
+}
+
+/**
+ * where the art for an input is saved, the same place relative to out_dir as the input is to base
+ *
+ * the format's extension is added after the image's own, so a.png and a.jpg don't both end up as a.txt
+ */
+pub fn output_path(input: &Path, base: &Path, out_dir: &Path, format: OutputFormat) -> PathBuf {
+    let relative = input.strip_prefix(base).unwrap_or_else(|_| Path::new(input.file_name().unwrap_or_default()));
+    let mut output = out_dir.join(relative).into_os_string();
+    output.push(".");
+    output.push(format.extension());
+    PathBuf::from(output)
+}
+
+/**
+ * renders every input into out_dir, carrying on past any that fail
+ */
+pub fn convert_all(inputs: &[PathBuf], base: &Path, out_dir: &Path, renderer: &AsciiRenderer, format: OutputFormat) -> BatchReport {
+    let mut report = BatchReport::default();
+    for input in inputs {
+        let output = output_path(input, base, out_dir, format);
+        let entry = match convert(input, &output, renderer, format) {
+            Ok(()) => BatchEntry { input: input.clone(), output: Some(output), error: None },
+            Err(e) => failed(input, &e),
+        };
+        report.entries.push(entry);
+    }
+    report
+}
+
+/**
+ * renders one image and saves it
+ */
+fn convert(input: &Path, output: &Path, renderer: &AsciiRenderer, format: OutputFormat) -> Result<(), Box<dyn Error>> {
+    let art = renderer.render_file(input)?;
+    let contents = match format {
+        OutputFormat::Text => art.into_string(),
+        OutputFormat::Ansi => art.to_ansi(),
+        OutputFormat::Html => art.to_html(&input.to_string_lossy()),
+        OutputFormat::Svg => art.to_svg(),
+    };
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(output, contents)?;
+    Ok(())
+}
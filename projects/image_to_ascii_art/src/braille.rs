@@ -0,0 +1,60 @@
+//! braille renderer, every character is a 2x4 block of pixels drawn with the Unicode Braille
+//! patterns (U+2800–U+28FF), giving 8 dots of detail per character instead of half a pixel
+
+use crate::dither::{self, Dither};
+
+/// first character of the Unicode Braille block, the one with no dots raised
+pub(crate) const BRAILLE_BASE: u32 = 0x2800;
+
+/// width and height, in pixels, of the block covered by one braille character
+pub const CELL_WIDTH: u32 = 2;
+pub const CELL_HEIGHT: u32 = 4;
+
+/// bit of the braille pattern for the dot at [y][x] inside a cell
+pub(crate) const DOT_BITS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/**
+ * renders an image as braille characters, a dot is raised for every dark pixel
+ *
+ * pixels darker than `threshold` are dark, if no threshold is given the midpoint between the
+ * brightest and darkest pixel is used. when dithering, the threshold is ignored and the dither
+ * decides between the brightest and darkest pixel instead
+ */
+pub fn gen_braille(img: &image::RgbImage, dither: Dither, threshold: Option<f32>) -> String {
+    let (width, height) = (img.width(), img.height());
+    let mut output = String::new();
+    if width == 0 || height == 0 {
+        return output;
+    }
+
+    let pixel_luminance = crate::luminance(img);
+    let (min, max) = crate::min_max(&pixel_luminance);
+
+    //decide which pixels are dark
+    let dark: Vec<bool> = match (dither, threshold) {
+        (Dither::None, Some(threshold)) => pixel_luminance.iter().map(|l| *l < threshold).collect(),
+        _ => dither::quantize(&pixel_luminance, width as usize, &[max, min], dither)
+            .iter()
+            .map(|level| *level == 1)
+            .collect(),
+    };
+
+    //walk the image one cell at a time, dots of cells hanging off the edge are left lowered
+    for cell_y in (0..height).step_by(CELL_HEIGHT as usize) {
+        for cell_x in (0..width).step_by(CELL_WIDTH as usize) {
+            let mut pattern = 0;
+            for (dy, row) in DOT_BITS.iter().enumerate() {
+                for (dx, bit) in row.iter().enumerate() {
+                    let (x, y) = (cell_x + dx as u32, cell_y + dy as u32);
+                    if x < width && y < height && dark[(y * width + x) as usize] {
+                        pattern |= bit;
+                    }
+                }
+            }
+            output.push(std::char::from_u32(BRAILLE_BASE + pattern).unwrap());
+        }
+        output.push('\n');
+    }
+
+    output
+}
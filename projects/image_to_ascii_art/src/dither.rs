@@ -0,0 +1,195 @@
+//! dithering, applied to the luminance of an image before glyphs are picked for it
+//!
+//! with only a handful of glyphs in the pallet, gradients band heavily when every pixel is simply
+//! matched to the closest glyph, dithering trades that banding for a pattern of glyphs whose
+//! average brightness is closer to the original image.
+//! every method here is deterministic, the same image always gives the same output
+
+use std::fmt;
+use std::str::FromStr;
+
+/// the dithering methods that can be applied before picking glyphs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dither {
+    /// no dithering, every pixel is matched to the closest level
+    #[default]
+    None,
+    /// Floyd–Steinberg error diffusion, spreads all of the error to 4 neighbors
+    FloydSteinberg,
+    /// Atkinson error diffusion, spreads 3/4 of the error to 6 neighbors, giving more contrast
+    Atkinson,
+    /// ordered dithering with a Bayer threshold matrix, the size must be a power of 2
+    Bayer(usize),
+}
+impl FromStr for Dither {
+    type Err = &'static str;
+
+    /**
+     * parses a dithering method from its name, as given on the command line
+     */
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Dither::None),
+            "floyd-steinberg" | "floyd_steinberg" | "fs" => Ok(Dither::FloydSteinberg),
+            "atkinson" => Ok(Dither::Atkinson),
+            "bayer" | "ordered" | "bayer4" => Ok(Dither::Bayer(4)),
+            "bayer2" => Ok(Dither::Bayer(2)),
+            "bayer8" => Ok(Dither::Bayer(8)),
+            _ => Err("unknown dithering method, expected one of: none, floyd-steinberg, atkinson, bayer2, bayer4, bayer8"),
+        }
+    }
+}
+impl fmt::Display for Dither {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dither::None => write!(f, "none"),
+            Dither::FloydSteinberg => write!(f, "floyd-steinberg"),
+            Dither::Atkinson => write!(f, "atkinson"),
+            Dither::Bayer(n) => write!(f, "bayer{}", n),
+        }
+    }
+}
+
+/**
+ * returns the index of the level closest to l, ties go to the first level
+ */
+pub fn nearest_level(l: f32, levels: &[f32]) -> usize {
+    let mut best_match = 0;
+    for i in 0..levels.len() {
+        if (l - levels[best_match]).abs() > (l - levels[i]).abs() {
+            best_match = i;
+        }
+    }
+    best_match
+}
+
+/**
+ * maps every luminance value to the index of a level, using the given dithering method
+ *
+ * `luminance` is the image in row major order, `width` pixels per row
+ */
+pub fn quantize(luminance: &[f32], width: usize, levels: &[f32], method: Dither) -> Vec<usize> {
+    if width == 0 || levels.is_empty() {
+        return vec![0; luminance.len()];
+    }
+    match method {
+        Dither::None => per_pixel(luminance, width, |_, _, l| nearest_level(l, levels)),
+        Dither::FloydSteinberg => diffuse(
+            luminance,
+            width,
+            levels,
+            &[(1, 0, 7.0 / 16.0), (-1, 1, 3.0 / 16.0), (0, 1, 5.0 / 16.0), (1, 1, 1.0 / 16.0)],
+        ),
+        Dither::Atkinson => diffuse(
+            luminance,
+            width,
+            levels,
+            &[
+                (1, 0, 1.0 / 8.0),
+                (2, 0, 1.0 / 8.0),
+                (-1, 1, 1.0 / 8.0),
+                (0, 1, 1.0 / 8.0),
+                (1, 1, 1.0 / 8.0),
+                (0, 2, 1.0 / 8.0),
+            ],
+        ),
+        Dither::Bayer(size) => ordered(luminance, width, levels, size),
+    }
+}
+
+/**
+ * error diffusion, walks the image in raster order and pushes the error of every pixel
+ * onto the neighbors listed in `kernel` as (dx, dy, weight)
+ */
+fn diffuse(luminance: &[f32], width: usize, levels: &[f32], kernel: &[(isize, usize, f32)]) -> Vec<usize> {
+    let height = luminance.len() / width;
+    //the error pushed onto a pixel is kept within the levels, or it builds up across the image
+    let (lo, hi) = levels.iter().fold((f32::MAX, f32::MIN), |(lo, hi), l| (lo.min(*l), hi.max(*l)));
+    let mut buffer = luminance.to_vec();
+    let mut output = Vec::with_capacity(luminance.len());
+
+    for y in 0..height {
+        for x in 0..width {
+            let l = buffer[y * width + x];
+            let best_match = nearest_level(l, levels);
+            let error = l - levels[best_match];
+            output.push(best_match);
+
+            for (dx, dy, weight) in kernel {
+                let nx = x as isize + dx;
+                let ny = y + dy;
+                if nx < 0 || nx as usize >= width || ny >= height {
+                    continue;
+                }
+                let neighbor = &mut buffer[ny * width + nx as usize];
+                *neighbor = (*neighbor + error * weight).clamp(lo, hi);
+            }
+        }
+    }
+
+    output
+}
+
+/**
+ * ordered dithering, nudges every pixel by the threshold in a tiled Bayer matrix before matching it
+ */
+fn ordered(luminance: &[f32], width: usize, levels: &[f32], size: usize) -> Vec<usize> {
+    let matrix = bayer_matrix(size);
+    let size = (matrix.len() as f64).sqrt() as usize;
+    let cells = (size * size) as f32;
+
+    //the nudge is scaled to the average distance between two levels
+    let (lo, hi) = levels.iter().fold((f32::MAX, f32::MIN), |(lo, hi), l| (lo.min(*l), hi.max(*l)));
+    let spread = if levels.len() > 1 { (hi - lo) / (levels.len() - 1) as f32 } else { 0.0 };
+
+    per_pixel(luminance, width, |x, y, l| {
+        let threshold = (matrix[(y % size) * size + x % size] as f32 + 0.5) / cells - 0.5;
+        nearest_level(l + threshold * spread, levels)
+    })
+}
+
+/**
+ * maps every pixel on its own with f(x, y, luminance), a row at a time on every core with the parallel feature
+ *
+ * error diffusion can't use this, every pixel depends on the ones before it
+ */
+fn per_pixel<F: Fn(usize, usize, f32) -> usize + Sync>(luminance: &[f32], width: usize, f: F) -> Vec<usize> {
+    let map_row = |(y, row): (usize, &[f32])| -> Vec<usize> {
+        row.iter().enumerate().map(|(x, l)| f(x, y, *l)).collect()
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        luminance.par_chunks(width).enumerate().flat_map_iter(map_row).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        luminance.chunks(width).enumerate().flat_map(map_row).collect()
+    }
+}
+
+/**
+ * builds a size*size Bayer matrix in row major order, sizes that aren't a power of 2 are rounded up
+ */
+pub fn bayer_matrix(size: usize) -> Vec<usize> {
+    let size = size.max(2).next_power_of_two();
+    let mut matrix = vec![0];
+    let mut n = 1;
+    //each step builds the 2n*2n matrix from the n*n one
+    while n < size {
+        let mut next = vec![0; 4 * n * n];
+        for y in 0..n {
+            for x in 0..n {
+                let m = 4 * matrix[y * n + x];
+                next[y * 2 * n + x] = m;
+                next[y * 2 * n + x + n] = m + 2;
+                next[(y + n) * 2 * n + x] = m + 3;
+                next[(y + n) * 2 * n + x + n] = m + 1;
+            }
+        }
+        matrix = next;
+        n *= 2;
+    }
+    matrix
+}
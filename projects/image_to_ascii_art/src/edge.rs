@@ -0,0 +1,144 @@
+//! edge detection with the Sobel operator, used to draw outlines with directional glyphs
+
+use crate::dither::Dither;
+use crate::palette::Palette;
+
+/// glyphs used along edges, by the direction the edge runs in
+const HORIZONTAL: char = '-';
+const HORIZONTAL_BELOW: char = '_';
+const VERTICAL: char = '|';
+const RISING: char = '/';
+const FALLING: char = '\\';
+
+/// the fraction of the strongest gradient an edge needs to reach, if none is given
+pub const DEFAULT_EDGE_THRESHOLD: f32 = 0.3;
+
+/// the gradient of the luminance at a pixel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gradient {
+    /// change in luminance left to right
+    pub gx: f32,
+    /// change in luminance top to bottom
+    pub gy: f32,
+}
+impl Gradient {
+    pub fn magnitude(&self) -> f32 {
+        (self.gx * self.gx + self.gy * self.gy).sqrt()
+    }
+
+    /**
+     * the angle the edge runs at, in degrees counter clockwise from horizontal, between 0 and 180
+     *
+     * the edge is perpendicular to the gradient
+     */
+    pub fn edge_angle(&self) -> f32 {
+        //the edge is the gradient turned 90 degrees, with y flipped so that up is positive
+        (-self.gx).atan2(-self.gy).to_degrees().rem_euclid(180.0)
+    }
+
+    /**
+     * picks the glyph that best follows the edge
+     */
+    pub fn glyph(&self) -> char {
+        let angle = self.edge_angle();
+        if !(22.5..157.5).contains(&angle) {
+            HORIZONTAL
+        } else if angle < 67.5 {
+            RISING
+        } else if angle < 112.5 {
+            VERTICAL
+        } else {
+            FALLING
+        }
+    }
+}
+
+/**
+ * applies the Sobel operator to the luminance of an image, pixels past the border are clamped to the edge
+ *
+ * `luminance` is the image in row major order, `width` pixels per row
+ */
+pub fn sobel(luminance: &[f32], width: usize) -> Vec<Gradient> {
+    if width == 0 {
+        return Vec::new();
+    }
+    let height = luminance.len() / width;
+    let at = |x: isize, y: isize| -> f32 {
+        let x = x.clamp(0, width as isize - 1) as usize;
+        let y = y.clamp(0, height as isize - 1) as usize;
+        luminance[y * width + x]
+    };
+
+    let row = |y: isize| -> Vec<Gradient> {
+        (0..width as isize)
+            .map(|x| {
+                let gx = (at(x + 1, y - 1) + 2.0 * at(x + 1, y) + at(x + 1, y + 1))
+                    - (at(x - 1, y - 1) + 2.0 * at(x - 1, y) + at(x - 1, y + 1));
+                let gy = (at(x - 1, y + 1) + 2.0 * at(x, y + 1) + at(x + 1, y + 1))
+                    - (at(x - 1, y - 1) + 2.0 * at(x, y - 1) + at(x + 1, y - 1));
+                Gradient { gx, gy }
+            })
+            .collect()
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        (0..height as isize).into_par_iter().flat_map_iter(row).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        (0..height as isize).flat_map(row).collect()
+    }
+}
+
+/**
+ * picks an edge glyph for every pixel on a strong edge, and None everywhere else
+ *
+ * an edge is strong when its gradient is at least `threshold` times the strongest gradient in the image
+ */
+pub fn edge_glyphs(img: &image::RgbImage, threshold: f32) -> Vec<Option<char>> {
+    let width = img.width() as usize;
+    let pixel_luminance = crate::luminance(img);
+    let gradients = sobel(&pixel_luminance, width);
+    let strongest = gradients.iter().map(Gradient::magnitude).fold(0.0, f32::max);
+    if strongest == 0.0 {
+        return vec![None; gradients.len()];
+    }
+
+    //how much the luminance changes going from the pixel at index to the one `offset` rows away, 0 past the border
+    let step = |index: usize, offset: isize| -> f32 {
+        let neighbor = index as isize + offset * width as isize;
+        if neighbor < 0 || neighbor as usize >= pixel_luminance.len() {
+            return 0.0;
+        }
+        (pixel_luminance[neighbor as usize] - pixel_luminance[index]).abs()
+    };
+
+    gradients
+        .iter()
+        .enumerate()
+        .map(|(index, g)| {
+            if g.magnitude() < threshold * strongest {
+                return None;
+            }
+            match g.glyph() {
+                //when the edge is between this pixel and the one below it, the line sits at the bottom of the character
+                HORIZONTAL if step(index, 1) > step(index, -1) => Some(HORIZONTAL_BELOW),
+                glyph => Some(glyph),
+            }
+        })
+        .collect()
+}
+
+/**
+ * renders an image like the block renderer, but with strong edges drawn using line characters
+ */
+pub fn gen_edges(img: &image::RgbImage, palette: &Palette, dither: Dither, threshold: f32) -> String {
+    let glyphs: Vec<char> = crate::pick_glyphs(img, palette, dither)
+        .into_iter()
+        .zip(edge_glyphs(img, threshold))
+        .map(|(glyph, edge)| edge.unwrap_or(glyph))
+        .collect();
+    crate::layout(&glyphs, img.width())
+}
@@ -0,0 +1,161 @@
+//! exporting rendered art as ANSI colored text, self-contained HTML or SVG, colored with the pixels it was rendered from
+
+use std::fmt::Write;
+
+/// size of the font in exported SVGs, in pixels
+pub const SVG_FONT_SIZE: f32 = 12.0;
+/// how far a monospace font advances per character, as a fraction of the font size
+const SVG_CHAR_WIDTH: f32 = 0.6;
+/// distance between two lines, as a fraction of the font size
+const SVG_LINE_HEIGHT: f32 = 1.2;
+
+/**
+ * finds the color of every character of rendered art, by averaging the pixels of the image it was rendered from
+ * that fall under the character
+ *
+ * the image must be the one the art was rendered from, after resizing
+ */
+pub fn cell_colors(art: &str, img: &image::RgbImage) -> Vec<Vec<image::Rgb<u8>>> {
+    let rows = art.lines().count() as u32;
+    let cols = art.lines().map(|l| l.chars().count()).max().unwrap_or(0) as u32;
+    let (width, height) = (img.width(), img.height());
+    if rows == 0 || cols == 0 || width == 0 || height == 0 {
+        return vec![Vec::new(); rows as usize];
+    }
+
+    //pixels under the character at (col, row), every character covers at least one pixel
+    let span = |cell: u32, cells: u32, pixels: u32| -> (u32, u32) {
+        let start = (cell * pixels / cells).min(pixels - 1);
+        let end = ((cell + 1) * pixels / cells).max(start + 1).min(pixels);
+        (start, end)
+    };
+
+    art.lines()
+        .enumerate()
+        .map(|(row, line)| {
+            let (y0, y1) = span(row as u32, rows, height);
+            (0..line.chars().count() as u32)
+                .map(|col| {
+                    let (x0, x1) = span(col, cols, width);
+                    let mut total = [0u32; 3];
+                    for y in y0..y1 {
+                        for x in x0..x1 {
+                            let image::Rgb(data) = *img.get_pixel(x, y);
+                            for channel in 0..3 {
+                                total[channel] += data[channel] as u32;
+                            }
+                        }
+                    }
+                    let count = (x1 - x0) * (y1 - y0);
+                    image::Rgb([(total[0] / count) as u8, (total[1] / count) as u8, (total[2] / count) as u8])
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/**
+ * formats a color as a #rrggbb hex code
+ */
+pub fn hex(color: &image::Rgb<u8>) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+/**
+ * escapes the characters that have a meaning in HTML and XML
+ */
+fn escape(c: char, out: &mut String) {
+    match c {
+        '&' => out.push_str("&amp;"),
+        '<' => out.push_str("&lt;"),
+        '>' => out.push_str("&gt;"),
+        '"' => out.push_str("&quot;"),
+        c => out.push(c),
+    }
+}
+
+/**
+ * splits a line into runs of characters with the same color, so each run only needs one tag
+ */
+fn color_runs(line: &str, colors: &[image::Rgb<u8>]) -> Vec<(String, image::Rgb<u8>)> {
+    let mut runs: Vec<(String, image::Rgb<u8>)> = Vec::new();
+    for (c, color) in line.chars().zip(colors.iter()) {
+        match runs.last_mut() {
+            Some((text, last)) if last == color => escape(c, text),
+            _ => {
+                let mut text = String::new();
+                escape(c, &mut text);
+                runs.push((text, *color));
+            }
+        }
+    }
+    runs
+}
+
+/**
+ * colors the art with 24 bit ANSI escape codes, the color is reset at the end of every line
+ */
+pub fn to_ansi(art: &str, colors: &[Vec<image::Rgb<u8>>]) -> String {
+    let mut ansi = String::new();
+    for (line, line_colors) in art.lines().zip(colors.iter()) {
+        let mut last = None;
+        for (c, color) in line.chars().zip(line_colors.iter()) {
+            if last != Some(color) {
+                write!(ansi, "\x1b[38;2;{};{};{}m", color[0], color[1], color[2]).unwrap();
+                last = Some(color);
+            }
+            ansi.push(c);
+        }
+        ansi.push_str("\x1b[0m\n");
+    }
+    ansi
+}
+
+/**
+ * builds a self-contained HTML page showing the art in a `<pre>`, with every character colored
+ */
+pub fn to_html(art: &str, colors: &[Vec<image::Rgb<u8>>], title: &str) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>");
+    title.chars().for_each(|c| escape(c, &mut html));
+    html.push_str("</title>\n<style>pre { font-family: monospace; line-height: 1; background: #ffffff; }</style>\n</head>\n<body>\n<pre>");
+    for (line, line_colors) in art.lines().zip(colors.iter()) {
+        for (text, color) in color_runs(line, line_colors) {
+            write!(html, "<span style=\"color:{}\">{}</span>", hex(&color), text).unwrap();
+        }
+        html.push('\n');
+    }
+    html.push_str("</pre>\n</body>\n</html>\n");
+    html
+}
+
+/**
+ * builds an SVG drawing the art one `<text>` row at a time, with every character colored
+ *
+ * every row is stretched to exactly the width a monospace font would give it, so columns line up
+ * whichever monospace font the viewer picks
+ */
+pub fn to_svg(art: &str, colors: &[Vec<image::Rgb<u8>>]) -> String {
+    let char_width = SVG_FONT_SIZE * SVG_CHAR_WIDTH;
+    let line_height = SVG_FONT_SIZE * SVG_LINE_HEIGHT;
+    let cols = art.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+    let rows = art.lines().count();
+    let (width, height) = (cols as f32 * char_width, rows as f32 * line_height);
+
+    let mut svg = String::new();
+    writeln!(svg, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.2}\" height=\"{h:.2}\" viewBox=\"0 0 {w:.2} {h:.2}\">", w = width, h = height).unwrap();
+    writeln!(svg, "<rect width=\"100%\" height=\"100%\" fill=\"#ffffff\"/>").unwrap();
+    writeln!(svg, "<g font-family=\"monospace\" font-size=\"{}\" xml:space=\"preserve\">", SVG_FONT_SIZE).unwrap();
+    for (row, (line, line_colors)) in art.lines().zip(colors.iter()).enumerate() {
+        //text is placed by its baseline, which sits about 80% of the way down the line
+        let y = row as f32 * line_height + SVG_FONT_SIZE * 0.8 + (line_height - SVG_FONT_SIZE) / 2.0;
+        let length = line.chars().count() as f32 * char_width;
+        write!(svg, "<text x=\"0\" y=\"{:.2}\" textLength=\"{:.2}\" lengthAdjust=\"spacingAndGlyphs\">", y, length).unwrap();
+        for (text, color) in color_runs(line, line_colors) {
+            write!(svg, "<tspan fill=\"{}\">{}</tspan>", hex(&color), text).unwrap();
+        }
+        svg.push_str("</text>\n");
+    }
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
@@ -1,113 +1,926 @@
 extern crate image;
-use image::GenericImageView;
+
+pub mod animation;
+pub mod batch;
+pub mod braille;
+pub mod dither;
+pub mod edge;
+pub mod export;
+pub mod palette;
+pub mod preprocess;
+pub mod raster;
+pub mod renderer;
+pub mod stream;
+pub mod terminal;
+pub use dither::Dither;
+pub use palette::Palette;
+pub use renderer::{AsciiArt, AsciiRenderer};
 
 use std::error::Error;  //allows for some better errors
 use std::fs;            //the library that will allow us to parse files
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 //use std::env;           //gives access to environment stuff
 
-//the image struct should include the filename, file path, extension, and image data
-
-//TESTs
-#[cfg(test)]
-mod tests {
-    //use super::*;
-
-    //add tests as i go along
+//IMAGE code
+/// the ways an image can be drawn with text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// every pixel becomes a shaded character from the palette, picked by luminance
+    #[default]
+    Blocks,
+    /// every 2x4 block of pixels becomes one braille character
+    Braille,
+    /// like blocks, but pixels on strong edges become line characters that follow the edge
+    Edges,
+}
+impl FromStr for Mode {
+    type Err = &'static str;
 
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "blocks" | "ascii" => Ok(Mode::Blocks),
+            "braille" => Ok(Mode::Braille),
+            "edges" | "edge" => Ok(Mode::Edges),
+            _ => Err("unknown mode, expected one of: blocks, braille, edges"),
+        }
+    }
 }
 
+/// lines of the terminal taken up by the title printed above the art
+const TITLE_ROWS: u32 = 4;
 
-//IMAGE code
+/// the command line options
 pub struct Config {
     pub file_path: String,
+    /// width of the art in characters, 0 fits it to the terminal
     pub width: u32,
-    pub img: image::DynamicImage,
+    /// most rows the art can take up, 0 fits it to the terminal
+    pub height: u32,
+    /// how many times taller than wide a character is
+    pub cell_aspect: f32,
+    pub mode: Mode,
+    pub palette: Palette,
+    pub dither: Dither,
+    pub threshold: Option<f32>,
+    pub edge_threshold: f32,
+    /// print the art in color
+    pub ansi: bool,
+    /// play every frame of an animated image in the terminal
+    pub play: bool,
+    /// how many times to play the animation, 0 plays it forever
+    pub loops: u32,
+    /// directory to save every frame to as a text file
+    pub frames_dir: Option<String>,
+    /// file to save an asciicast recording of every frame to
+    pub cast_path: Option<String>,
+    /// file to save the art to as a colored HTML page
+    pub html_path: Option<String>,
+    /// file to save the art to as a colored SVG
+    pub svg_path: Option<String>,
+    /// directory to save art to when converting a directory or glob of images
+    pub out_dir: Option<String>,
+    /// file to save a JSON manifest of a batch conversion to
+    pub manifest_path: Option<String>,
+    /// format of the files saved by a batch conversion
+    pub format: batch::OutputFormat,
+    /// draw the file, which is text (ANSI colors and all), into this PNG instead of rendering an image
+    pub text_to_image: Option<String>,
+    /// image to compare the drawn text to, printing how similar they are
+    pub compare_path: Option<String>,
+    /// transforms run on the image before rendering, in the order they were given
+    pub preprocess: preprocess::Pipeline,
 }
 impl Config {
     /**
-     * creates a new image from the passed args (taken from command line)
+     * parses the passed args (taken from command line)
+     *
+     * the file path can also be a directory or a glob like "scans/**/*.png", to convert every image in it
+     *
+     * usage: `image_to_ascii_art <file path> [width] [--height=<rows>] [--cell-aspect=<ratio>] [--mode=<mode>]
+     *         [--palette=<name>] [--glyphs=<characters, brightest first>] [--ansi]
+     *         [--dither=<method>] [--threshold=<0-255>] [--edge-threshold=<0-1>]
+     *         [--play] [--loops=<n>] [--frames-dir=<dir>] [--cast=<file>]
+     *         [--html=<file>] [--svg=<file>]
+     *         [--out-dir=<dir>] [--manifest=<file>] [--format=<txt|ansi|html|svg>]
+     *         [--crop=<x>,<y>,<width>,<height>] [--rotate=<90|180|270>] [--flip=<horizontal|vertical>]
+     *         [--binarize=<0-255>] [--blur=<sigma>] [--sharpen=<sigma>[,<threshold>]] [--auto-crop[=<tolerance>]]`
+     *
+     * --play and the other animation flags take animated GIFs and APNGs, video files aren't supported
+     *
+     * the preprocessing flags (crop to auto-crop) are run on the image in the order they're given
+     * or, to draw a text file back into an image:
+     * `image_to_ascii_art <text file> --text-to-image=<png file> [--compare=<image file>]`
      */
     pub fn new(args: &[String]) -> Result<Config, &str> {
         // parse arguments
-        if args.len() < 3 { // not enough arguments (will always be at least 2)
+        if args.len() < 2 { // not enough arguments (will always be at least 1)
             return Err("not enough arguments");
         }
         let file_path = args[1].clone();//get file path from input
-        let mut width:u32 = args[2].parse().unwrap_or_default();
-        if width == 0 {width = 100;}
-        let img = image::open(&file_path).unwrap(); // open image
+        //the width is optional, when it's missing or 0 the art is fit to the terminal
+        let mut flags = &args[2..];
+        let mut width:u32 = 0;
+        if let Some(arg) = flags.first() {
+            if !arg.starts_with("--") {
+                width = arg.parse().unwrap_or_default();
+                flags = &flags[1..];
+            }
+        }
+
+        //optional flags
+        let mut height = 0;
+        let mut cell_aspect = terminal::DEFAULT_CELL_ASPECT;
+        let mut mode = Mode::Blocks;
+        let mut palette = Palette::default();
+        let mut dither = Dither::None;
+        let mut threshold = None;
+        let mut edge_threshold = edge::DEFAULT_EDGE_THRESHOLD;
+        let mut ansi = false;
+        let mut play = false;
+        let mut loops = 1;
+        let mut frames_dir = None;
+        let mut cast_path = None;
+        let mut html_path = None;
+        let mut svg_path = None;
+        let mut out_dir = None;
+        let mut manifest_path = None;
+        let mut format = batch::OutputFormat::Text;
+        let mut text_to_image = None;
+        let mut compare_path = None;
+        let mut preprocess = preprocess::Pipeline::new();
+        for arg in flags {
+            if let Some(value) = arg.strip_prefix("--height=") {
+                height = value.parse().map_err(|_| "height must be a whole number of rows")?;
+            } else if let Some(value) = arg.strip_prefix("--cell-aspect=") {
+                cell_aspect = value.parse().map_err(|_| "cell aspect must be a number")?;
+                if cell_aspect <= 0.0 {return Err("cell aspect must be greater than 0");}
+            } else if let Some(name) = arg.strip_prefix("--mode=") {
+                mode = name.parse()?;
+            } else if let Some(name) = arg.strip_prefix("--palette=") {
+                palette = name.parse()?;
+            } else if let Some(glyphs) = arg.strip_prefix("--glyphs=") {
+                palette = Palette::new(glyphs)?;
+            } else if arg == "--ansi" {
+                ansi = true;
+            } else if let Some(method) = arg.strip_prefix("--dither=") {
+                dither = method.parse()?;
+            } else if let Some(value) = arg.strip_prefix("--threshold=") {
+                threshold = Some(value.parse::<u8>().map_err(|_| "threshold must be between 0 and 255")? as f32);
+            } else if let Some(value) = arg.strip_prefix("--edge-threshold=") {
+                edge_threshold = value.parse().map_err(|_| "edge threshold must be a number between 0 and 1")?;
+            } else if arg == "--play" {
+                play = true;
+            } else if let Some(value) = arg.strip_prefix("--loops=") {
+                loops = value.parse().map_err(|_| "loops must be a whole number, 0 to loop forever")?;
+            } else if let Some(dir) = arg.strip_prefix("--frames-dir=") {
+                frames_dir = Some(dir.to_string());
+            } else if let Some(path) = arg.strip_prefix("--cast=") {
+                cast_path = Some(path.to_string());
+            } else if let Some(path) = arg.strip_prefix("--html=") {
+                html_path = Some(path.to_string());
+            } else if let Some(path) = arg.strip_prefix("--svg=") {
+                svg_path = Some(path.to_string());
+            } else if let Some(dir) = arg.strip_prefix("--out-dir=") {
+                out_dir = Some(dir.to_string());
+            } else if let Some(path) = arg.strip_prefix("--manifest=") {
+                manifest_path = Some(path.to_string());
+            } else if let Some(name) = arg.strip_prefix("--format=") {
+                format = name.parse()?;
+            } else if let Some(path) = arg.strip_prefix("--text-to-image=") {
+                text_to_image = Some(path.to_string());
+            } else if let Some(path) = arg.strip_prefix("--compare=") {
+                compare_path = Some(path.to_string());
+            } else if let Some(value) = arg.strip_prefix("--crop=") {
+                let rect: Vec<u32> = value.split(',').map(|v| v.parse()).collect::<Result<_, _>>().map_err(|_| "crop must be 4 whole numbers, x,y,width,height")?;
+                if rect.len() != 4 {return Err("crop must be 4 whole numbers, x,y,width,height");}
+                preprocess = preprocess.crop(rect[0], rect[1], rect[2], rect[3])?;
+            } else if let Some(value) = arg.strip_prefix("--rotate=") {
+                preprocess = preprocess.rotate(value.parse().map_err(|_| "rotation must be 90, 180 or 270 degrees")?)?;
+            } else if let Some(value) = arg.strip_prefix("--flip=") {
+                preprocess = preprocess.flip(value.parse()?);
+            } else if let Some(value) = arg.strip_prefix("--binarize=") {
+                preprocess = preprocess.threshold(value.parse().map_err(|_| "binarize threshold must be between 0 and 255")?);
+            } else if let Some(value) = arg.strip_prefix("--blur=") {
+                preprocess = preprocess.blur(value.parse().map_err(|_| "blur must be a number")?);
+            } else if let Some(value) = arg.strip_prefix("--sharpen=") {
+                let (sigma, threshold) = value.split_once(',').unwrap_or((value, "0"));
+                let sigma = sigma.parse().map_err(|_| "sharpen must be a number, optionally followed by a whole number threshold")?;
+                let threshold = threshold.parse().map_err(|_| "sharpen must be a number, optionally followed by a whole number threshold")?;
+                preprocess = preprocess.sharpen(sigma, threshold);
+            } else if arg == "--auto-crop" {
+                preprocess = preprocess.auto_crop(0);
+            } else if let Some(value) = arg.strip_prefix("--auto-crop=") {
+                preprocess = preprocess.auto_crop(value.parse().map_err(|_| "auto crop tolerance must be between 0 and 255")?);
+            } else {
+                return Err("unknown option");
+            }
+        }
 
         //return
-        Ok(Config { file_path, width, img } )
+        Ok(Config { file_path, width, height, cell_aspect, mode, palette, dither, threshold, edge_threshold, ansi, play, loops, frames_dir, cast_path, html_path, svg_path, out_dir, manifest_path, format, text_to_image, compare_path, preprocess } )
+    }
+
+    /**
+     * builds a renderer with the options, sized to never go past the edges of the terminal
+     *
+     * when stdout isn't a terminal the width and height asked for are used as is, and 80x24 is used
+     * for the ones that weren't asked for
+     */
+    pub fn renderer(&self) -> AsciiRenderer {
+        self.renderer_within(terminal::terminal_size())
     }
 
+    /**
+     * builds a renderer with the options, sized to fit a terminal of (columns, rows), or no terminal
+     */
+    pub fn renderer_within(&self, terminal: Option<(u32, u32)>) -> AsciiRenderer {
+        let terminal = terminal.map(|(cols, rows)| (cols, rows.saturating_sub(TITLE_ROWS).max(1)));
+        let limit = |asked: u32, available: Option<u32>, fallback: u32| match (asked, available) {
+            (0, Some(available)) => available,
+            (0, None) => fallback,
+            (asked, Some(available)) => asked.min(available),
+            (asked, None) => asked,
+        };
+        let width = limit(self.width, terminal.map(|t| t.0), terminal::FALLBACK_SIZE.0);
+        //a height of 0 doesn't limit the rows, which is only wanted when a width was asked for and there's no terminal
+        let height = limit(self.height, terminal.map(|t| t.1), if self.width == 0 {terminal::FALLBACK_SIZE.1} else {0});
+
+        AsciiRenderer::new()
+            .width(width)
+            .height(height)
+            .cell_aspect(self.cell_aspect)
+            .mode(self.mode)
+            .palette(self.palette.clone())
+            .dither(self.dither)
+            .threshold(self.threshold)
+            .edge_threshold(self.edge_threshold)
+            .preprocess(self.preprocess.clone())
+    }
 }
 
 pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
+    if config.text_to_image.is_some() {
+        return run_text_to_image(&config);
+    }
+    if batch::is_batch(&config.file_path) || config.out_dir.is_some() {
+        return run_batch(&config);
+    }
+    if config.play || config.frames_dir.is_some() || config.cast_path.is_some() {
+        return run_animation(&config);
+    }
+
+    let art = config.renderer().render_file(&config.file_path)?; // open image
+
+    //save ascii art of whole image
+    fs::write("./ascii_art.txt", art.as_str())?;
+
     //call to another function to do the expensive stuff
     println!("{} as ascii art (resolution reduced to fit in terminal): \n\n", config.file_path);
-    println!("{}",image_to_ascii(&config));
+    if config.ansi {
+        println!("{}", art.to_ansi());
+    } else {
+        println!("{}", art);
+    }
+
+    //colored exports
+    if let Some(path) = &config.html_path {
+        fs::write(path, art.to_html(&config.file_path))?;
+    }
+    if let Some(path) = &config.svg_path {
+        fs::write(path, art.to_svg())?;
+    }
 
     //return
     Ok(())
 }
 
-pub fn image_to_ascii(config: &Config) -> String {
-    //let mut txt_output: Vec<&str> = Vec::new();
-    let printable_output: String;
+/**
+ * draws a text file into a PNG, and compares it to an image if asked to
+ */
+fn run_text_to_image(config: &Config) -> Result<(), Box<dyn Error>> {
+    let output = config.text_to_image.as_ref().ok_or("no image to draw the text into")?;
+    let text = fs::read_to_string(&config.file_path)?;
+    let img = raster::rasterize(&text);
+    img.save_with_format(output, image::ImageFormat::Png)?;
+    println!("drew {} into {} ({}x{})", config.file_path, output, img.width(), img.height());
+
+    if let Some(path) = &config.compare_path {
+        let similarity = raster::similarity(&image::DynamicImage::ImageRgb8(img), &image::open(path)?);
+        println!("similarity to {}: {:.1}%", path, similarity * 100.0);
+    }
+
+    Ok(())
+}
+
+/**
+ * renders every image in a directory or glob into the output directory, then prints a summary
+ */
+fn run_batch(config: &Config) -> Result<(), Box<dyn Error>> {
+    let out_dir = config.out_dir.as_ref().ok_or("converting more than one image needs an --out-dir")?;
+    let inputs = batch::collect_inputs(&config.file_path)?;
+    if inputs.images.is_empty() && inputs.unreadable.is_empty() {
+        return Err(format!("no images found in {}", config.file_path).into());
+    }
+
+    //the art is going to files, so the terminal doesn't limit its size
+    let renderer = config.renderer_within(None);
+    let mut report = batch::convert_all(&inputs.images, &inputs.base, std::path::Path::new(out_dir), &renderer, config.format);
+    //directories that couldn't be read are failures too, listed after the images
+    report.entries.extend(inputs.unreadable);
 
-    let img = &config.img;
+    print!("{}", report);
+    if let Some(path) = &config.manifest_path {
+        fs::write(path, report.to_json())?;
+    }
 
-    //generate ascii art of whole image
-    fs::write("./ascii_art.txt", format!("{}",gen_ascii(img.resize_to_fill(config.width,((config.width*img.height()) as f32 / img.width() as f32) as u32, image::imageops::Gaussian).into_rgb8()))).expect("Couldn't write to file");
+    Ok(())
+}
 
-    //generate ascii art of resized image
-    printable_output = String::from(gen_ascii(img.resize_to_fill(config.width,((config.width*img.height()) as f32 / img.width() as f32) as u32, image::imageops::Gaussian).into_rgb8()));
+/**
+ * renders every frame of an animated image, then plays and/or saves them
+ */
+fn run_animation(config: &Config) -> Result<(), Box<dyn Error>> {
+    let renderer = config.renderer();
+    let frames: Vec<animation::Frame> = animation::load_frames(&config.file_path)?
+        .into_iter()
+        .map(|frame| {
+            let delay = animation::frame_delay(&frame);
+            let art = renderer.render(&image::DynamicImage::ImageRgba8(frame.into_buffer()));
+            animation::Frame { art: if config.ansi {art.to_ansi()} else {art.into_string()}, delay }
+        })
+        .collect();
 
-    return printable_output;
+    if let Some(dir) = &config.frames_dir {
+        animation::write_text_frames(&frames, std::path::Path::new(dir))?;
+    }
+    if let Some(path) = &config.cast_path {
+        animation::write_asciicast(&frames, &mut std::io::BufWriter::new(fs::File::create(path)?))?;
+    }
+    if config.play {
+        //Ctrl-C stops playback instead of killing the process, so the cursor gets shown again
+        static STOP: AtomicBool = AtomicBool::new(false);
+        ctrlc::set_handler(|| STOP.store(true, Ordering::Relaxed))?;
+        animation::play(&frames, config.loops, &STOP, &mut std::io::stdout())?;
+    }
+
+    Ok(())
 }
-fn gen_ascii(img: image::RgbImage) -> String {
-    let mut output: String = String::new();
-    let pixel_luminance: Vec<f32>;
-    const PALLET_SIZE:usize = 5;
-    let ascii_characters: [char; PALLET_SIZE] = [' ','░','▒','▓','█']; //pretty much the pallet
-    let mut luminance_scale: [f32;PALLET_SIZE] = [0.0;PALLET_SIZE];
 
+/**
+ * renders the image with the options from the command line
+ */
+pub fn image_to_ascii(config: &Config, img: &image::DynamicImage) -> String {
+    config.renderer().render(img).into_string()
+}
+
+pub(crate) fn gen_ascii(img: &image::RgbImage, palette: &Palette, dither: Dither) -> String {
+    layout(&pick_glyphs(img, palette, dither), img.width())
+}
+
+/**
+ * picks the palette character closest to the darkness of every pixel, in row major order
+ */
+pub(crate) fn pick_glyphs(img: &image::RgbImage, palette: &Palette, dither: Dither) -> Vec<char> {
     //calculate and store lumincance of every pixel here to save time
-    pixel_luminance = img.pixels().map(|p| {
-        let image::Rgb(data) = *p;
-        let (r,g,b) = (data[0] as f32, data[1] as f32, data[2] as f32);
-        ((0.299*r*r + 0.587*g*g + 0.114*b*b)).sqrt()
-    }).collect();
+    let pixel_luminance = luminance(img);
     
     //find the brightest and darkest, to use to scale rest of image
-    let mut min = *pixel_luminance.get(0).unwrap();
-    let mut max = *pixel_luminance.get(0).unwrap();
+    let (min, max) = min_max(&pixel_luminance);
+
+    //calculate luminance scale for image
+    let luminance_scale = palette.levels(min, max);
+
+    //match every pixel to the character closest to its darkness, dithering if asked to
+    let matches = dither::quantize(&pixel_luminance, img.width() as usize, &luminance_scale, dither);
+    matches.iter().map(|best_match| palette.glyphs()[*best_match]).collect()
+}
+
+/**
+ * lays out one glyph per pixel as lines of text
+ */
+pub(crate) fn layout(glyphs: &[char], width: u32) -> String {
+    let mut output: String = String::new();
+    for (index, glyph) in glyphs.iter().enumerate() {
+        output.push(*glyph);
+
+        if (index + 1) % width as usize==0 {output.push('\n');}
+    }
+    output
+}
+
+/**
+ * calculates the perceived luminance of every pixel, in row major order
+ */
+pub(crate) fn luminance(img: &image::RgbImage) -> Vec<f32> {
+    let pixel_luminance = |data: &[u8]| {
+        let (r,g,b) = (data[0] as f32, data[1] as f32, data[2] as f32);
+        (0.299*r*r + 0.587*g*g + 0.114*b*b).sqrt()
+    };
+
+    //one row per task
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        let row_len = (img.width() as usize * 3).max(1);
+        img.as_raw().par_chunks(row_len).flat_map_iter(|row| row.chunks(3).map(pixel_luminance)).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        img.as_raw().chunks(3).map(pixel_luminance).collect()
+    }
+}
+
+/**
+ * finds the darkest and brightest luminance
+ */
+pub(crate) fn min_max(pixel_luminance: &[f32]) -> (f32, f32) {
+    let mut min = *pixel_luminance.first().unwrap();
+    let mut max = *pixel_luminance.first().unwrap();
     for l in pixel_luminance.iter() {
         if *l > max {max = *l;}
         else if *l < min {min = *l;}
     }
+    (min, max)
+}
 
-    //calculate luminance scale for image
-    for i in 1..=PALLET_SIZE {
-        luminance_scale[i-1] = (max-min)/(i) as f32;
+
+//TESTs
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //add tests as i go along
+
+    /// a horizontal gradient from black to white
+    fn gradient(width: u32, height: u32) -> image::RgbImage {
+        image::RgbImage::from_fn(width, height, |x, _| {
+            let v = (x * 255 / (width - 1)) as u8;
+            image::Rgb([v, v, v])
+        })
     }
 
-    //parse image once more, and add the character closest to the darkness of each pixel to output
-    for (index, l) in pixel_luminance.iter().enumerate() {
-        //find closest match
-        let mut best_match = 0;
-        for i in 0..PALLET_SIZE {
-            if (*l-luminance_scale[best_match]).abs() > (*l-luminance_scale[i]).abs() {
-                best_match = i;
+    #[test]
+    fn bayer_matrix_is_a_permutation() {
+        for size in [2, 4, 8].iter() {
+            let mut matrix = dither::bayer_matrix(*size);
+            matrix.sort_unstable();
+            assert_eq!(matrix, (0..size * size).collect::<Vec<usize>>());
+        }
+        assert_eq!(dither::bayer_matrix(2), vec![0, 2, 3, 1]);
+    }
+
+    #[test]
+    fn parses_dither_names() {
+        assert_eq!("floyd-steinberg".parse(), Ok(Dither::FloydSteinberg));
+        assert_eq!("Atkinson".parse(), Ok(Dither::Atkinson));
+        assert_eq!("bayer8".parse(), Ok(Dither::Bayer(8)));
+        assert!("sierra".parse::<Dither>().is_err());
+    }
+
+    #[test]
+    fn dithering_is_deterministic() {
+        for method in [Dither::FloydSteinberg, Dither::Atkinson, Dither::Bayer(4)].iter() {
+            assert_eq!(gen_ascii(&gradient(40, 8), &Palette::blocks(), *method), gen_ascii(&gradient(40, 8), &Palette::blocks(), *method));
+        }
+    }
+
+    #[test]
+    fn dithering_breaks_up_bands() {
+        //count how many times the glyph changes along each row, dithering should change it more often
+        let transitions = |art: String| -> usize {
+            art.lines()
+                .map(|line| {
+                    let glyphs: Vec<char> = line.chars().collect();
+                    glyphs.windows(2).filter(|w| w[0] != w[1]).count()
+                })
+                .sum()
+        };
+        let plain = transitions(gen_ascii(&gradient(64, 8), &Palette::blocks(), Dither::None));
+        for method in [Dither::FloydSteinberg, Dither::Atkinson, Dither::Bayer(4)].iter() {
+            assert!(transitions(gen_ascii(&gradient(64, 8), &Palette::blocks(), *method)) > plain, "{} didn't dither", method);
+        }
+    }
+
+    #[test]
+    fn braille_packs_2x4_blocks() {
+        //left column of every cell dark, right column bright
+        let img = image::RgbImage::from_fn(4, 8, |x, _| if x % 2 == 0 {image::Rgb([0, 0, 0])} else {image::Rgb([255, 255, 255])});
+        assert_eq!(braille::gen_braille(&img, Dither::None, None), "\u{2847}\u{2847}\n\u{2847}\u{2847}\n");
+
+        //a single dark pixel in the bottom right corner, in an image that doesn't fill the last cell
+        let mut img = image::RgbImage::from_pixel(3, 5, image::Rgb([255, 255, 255]));
+        img.put_pixel(1, 3, image::Rgb([0, 0, 0]));
+        assert_eq!(braille::gen_braille(&img, Dither::None, None), "\u{2880}\u{2800}\n\u{2800}\u{2800}\n");
+    }
+
+    #[test]
+    fn braille_threshold() {
+        let img = gradient(8, 4);
+        let all_dots = braille::gen_braille(&img, Dither::None, Some(256.0));
+        assert_eq!(all_dots, "\u{28FF}".repeat(4) + "\n");
+        let no_dots = braille::gen_braille(&img, Dither::None, Some(0.0));
+        assert_eq!(no_dots, "\u{2800}".repeat(4) + "\n");
+    }
+
+    #[test]
+    fn sobel_edge_directions() {
+        let glyph_at = |img: &image::RgbImage, x: usize, y: usize| {
+            edge::edge_glyphs(img, 0.0)[y * img.width() as usize + x].unwrap()
+        };
+        //dark left half, bright right half
+        let vertical = image::RgbImage::from_fn(6, 6, |x, _| if x < 3 {image::Rgb([0, 0, 0])} else {image::Rgb([255, 255, 255])});
+        assert_eq!(glyph_at(&vertical, 2, 3), '|');
+        //dark top half, bright bottom half
+        let horizontal = image::RgbImage::from_fn(6, 6, |_, y| if y < 3 {image::Rgb([0, 0, 0])} else {image::Rgb([255, 255, 255])});
+        assert_eq!(glyph_at(&horizontal, 3, 2), '_');
+        assert_eq!(glyph_at(&horizontal, 3, 3), '-');
+        //dark above the rising diagonal, bright below it
+        let rising = image::RgbImage::from_fn(6, 6, |x, y| if x + y < 6 {image::Rgb([0, 0, 0])} else {image::Rgb([255, 255, 255])});
+        assert_eq!(glyph_at(&rising, 3, 3), '/');
+        //dark above the falling diagonal, bright below it
+        let falling = image::RgbImage::from_fn(6, 6, |x, y| if y > x {image::Rgb([255, 255, 255])} else {image::Rgb([0, 0, 0])});
+        assert_eq!(glyph_at(&falling, 2, 3), '\\');
+    }
+
+    #[test]
+    fn edges_fall_back_to_the_pallet() {
+        //a black square on white, outlined by edge glyphs and filled from the pallet
+        let img = image::RgbImage::from_fn(9, 9, |x, y| if (3..6).contains(&x) && (3..6).contains(&y) {image::Rgb([0, 0, 0])} else {image::Rgb([255, 255, 255])});
+        let art = edge::gen_edges(&img, &Palette::blocks(), Dither::None, edge::DEFAULT_EDGE_THRESHOLD);
+        let lines: Vec<&str> = art.lines().collect();
+        assert_eq!(lines.len(), 9);
+        assert_eq!(lines[0], " ".repeat(9));
+        assert!(lines[4].contains('|'));
+        assert!(lines[2].contains("__"));
+        assert!(edge::gen_edges(&image::RgbImage::from_pixel(4, 4, image::Rgb([9, 9, 9])), &Palette::blocks(), Dither::None, 0.3).chars().all(|c| c == ' ' || c == '\n'));
+    }
+
+    #[test]
+    fn decodes_animated_gif_frames() {
+        //encode a 2 frame gif, black for 50ms then white for 200ms
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = image::codecs::gif::GifEncoder::new(&mut bytes);
+            for (value, delay) in [(0, 50), (255, 200)].iter() {
+                let buffer = image::RgbaImage::from_pixel(4, 4, image::Rgba([*value, *value, *value, 255]));
+                let frame = image::Frame::from_parts(buffer, 0, 0, image::Delay::from_numer_denom_ms(*delay, 1));
+                encoder.encode_frame(frame).unwrap();
             }
         }
 
-        output.push(ascii_characters[best_match]);
-        output.push(ascii_characters[best_match]);
+        let frames = animation::frames_from_memory(&bytes).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(animation::frame_delay(&frames[0]), std::time::Duration::from_millis(50));
+        assert_eq!(animation::frame_delay(&frames[1]), std::time::Duration::from_millis(200));
+        assert_eq!(frames[0].buffer().get_pixel(0, 0)[0], 0);
+        assert_eq!(frames[1].buffer().get_pixel(0, 0)[0], 255);
+    }
+
+    #[test]
+    fn still_images_are_one_frame() {
+        let bytes = fs::read("simple_4_color_gray.png").unwrap();
+        assert_eq!(animation::frames_from_memory(&bytes).unwrap().len(), 1);
+        //video containers get a clear error instead of an unknown format
+        let mp4 = b"\0\0\0\x18ftypmp42\0\0\0\0";
+        assert!(animation::frames_from_memory(mp4).err().unwrap().to_string().contains("video files aren't supported"));
+    }
+
+    #[test]
+    fn playback_shows_the_cursor_again() {
+        let frames = vec![animation::Frame { art: String::from("ab\n"), delay: std::time::Duration::from_millis(0) }];
+        let mut out = Vec::new();
+        animation::play(&frames, 2, &std::sync::atomic::AtomicBool::new(false), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("\x1b[?25l") && out.ends_with("\x1b[?25h"));
+        assert_eq!(out.matches("ab\n").count(), 2);
+
+        //stopped before the first frame, the cursor still comes back
+        let mut out = Vec::new();
+        animation::play(&frames, 0, &std::sync::atomic::AtomicBool::new(true), &mut out).unwrap();
+        assert!(String::from_utf8(out).unwrap().ends_with("\x1b[?25h"));
+    }
+
+    #[test]
+    fn writes_asciicast() {
+        let frames = vec![
+            animation::Frame { art: String::from("ab\n\"c\n"), delay: std::time::Duration::from_millis(250) },
+            animation::Frame { art: String::from("xyz\n"), delay: std::time::Duration::from_millis(250) },
+        ];
+        let mut cast = Vec::new();
+        animation::write_asciicast(&frames, &mut cast).unwrap();
+        let cast = String::from_utf8(cast).unwrap();
+        let lines: Vec<&str> = cast.lines().collect();
+        assert_eq!(lines[0], "{\"version\": 2, \"width\": 3, \"height\": 2}");
+        assert_eq!(lines[1], "[0.000000, \"o\", \"\\u001b[2J\\u001b[Hab\\r\\n\\\"c\\r\\n\"]");
+        assert_eq!(lines[2], "[0.250000, \"o\", \"\\u001b[Hxyz\\r\\n\"]");
+    }
+
+    #[test]
+    fn cell_colors_average_the_pixels_under_each_character() {
+        //red left half, blue right half
+        let img = image::RgbImage::from_fn(4, 2, |x, _| if x < 2 {image::Rgb([255, 0, 0])} else {image::Rgb([0, 0, 255])});
+        //one pixel per 2 characters
+        let colors = export::cell_colors("abcdefgh\nabcdefgh\n", &img);
+        assert_eq!(colors.len(), 2);
+        assert_eq!(colors[0][3], image::Rgb([255, 0, 0]));
+        assert_eq!(colors[0][4], image::Rgb([0, 0, 255]));
+        //2x2 pixels per character, straddling both halves in the middle
+        let colors = export::cell_colors("abc\n", &img);
+        assert_eq!(colors[0][0], image::Rgb([255, 0, 0]));
+        assert_eq!(colors[0][2], image::Rgb([0, 0, 255]));
+    }
+
+    #[test]
+    fn html_export_groups_colors_and_escapes() {
+        let red = image::Rgb([255, 0, 0]);
+        let blue = image::Rgb([0, 0, 255]);
+        let html = export::to_html("<<&\n", &[vec![red, red, blue]], "a<b");
+        assert!(html.contains("<title>a&lt;b</title>"));
+        assert!(html.contains("<pre><span style=\"color:#ff0000\">&lt;&lt;</span><span style=\"color:#0000ff\">&amp;</span>\n</pre>"));
+    }
+
+    #[test]
+    fn svg_export_has_a_row_per_line() {
+        let gray = image::Rgb([128, 128, 128]);
+        let svg = export::to_svg("ab\ncd\n", &[vec![gray, gray], vec![gray, gray]]);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"14.40\" height=\"28.80\""));
+        assert_eq!(svg.matches("<text ").count(), 2);
+        assert!(svg.contains("<tspan fill=\"#808080\">cd</tspan>"));
+    }
+
+    #[test]
+    fn fits_within_width_and_height() {
+        //a square image is half as many rows as columns with the default cell aspect
+        assert_eq!(terminal::fit(100, 100, 80, Some(50), 2.0), (80, 40));
+        //too tall for the rows available, so it gets narrower instead
+        assert_eq!(terminal::fit(100, 400, 80, Some(24), 2.0), (12, 24));
+        //square cells
+        assert_eq!(terminal::fit(200, 100, 60, None, 1.0), (60, 30));
+        //never less than one character
+        assert_eq!(terminal::fit(10_000, 1, 80, Some(24), 2.0), (80, 1));
+    }
+
+    #[test]
+    fn layout_doesnt_double_glyphs() {
+        assert_eq!(layout(&['a', 'b', 'c', 'd'], 2), "ab\ncd\n");
+    }
+
+    #[test]
+    fn renderer_builder() {
+        let img = image::DynamicImage::ImageRgb8(gradient(64, 32));
+        let art = AsciiRenderer::new().width(16).render(&img);
+        assert_eq!((art.width(), art.height()), (16, 4));
+        //limited by height, so it gets narrower
+        let art = AsciiRenderer::new().width(16).height(2).render(&img);
+        assert_eq!((art.width(), art.height()), (8, 2));
+        //square cells need twice the rows
+        let art = AsciiRenderer::new().width(16).cell_aspect(1.0).render(&img);
+        assert_eq!((art.width(), art.height()), (16, 8));
+
+        let art = AsciiRenderer::new().width(10).palette(Palette::ascii()).render(&img);
+        assert!(art.as_str().chars().all(|c| c == '\n' || Palette::ascii().glyphs().contains(&c)));
+        let art = AsciiRenderer::new().width(10).mode(Mode::Braille).render(&img);
+        assert!(art.as_str().chars().all(|c| c == '\n' || ('\u{2800}'..='\u{28FF}').contains(&c)));
+    }
 
-        if (index + 1) % img.width()as usize==0 {output.push('\n');}
+    #[test]
+    fn renders_encoded_bytes() {
+        let bytes = fs::read("simple_4_color_gray.png").unwrap();
+        let renderer = AsciiRenderer::new().width(20);
+        let art = renderer.render_bytes(&bytes).unwrap();
+        assert_eq!(art, renderer.render(&image::open("simple_4_color_gray.png").unwrap()));
+        assert_eq!(art.colors().len(), art.height());
+        assert!(renderer.render_bytes(b"not an image").is_err());
     }
 
-    return output;
-}
\ No newline at end of file
+    #[test]
+    fn art_serializations() {
+        let img = image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(4, 2, image::Rgb([255, 0, 0])));
+        let art = AsciiRenderer::new().width(2).render(&img);
+        assert_eq!(art.to_string(), art.as_str());
+        assert_eq!(art.to_ansi(), "\x1b[38;2;255;0;0m  \x1b[0m\n");
+        assert!(art.to_html("red").contains("<span style=\"color:#ff0000\">  </span>"));
+        assert!(art.to_svg().contains("<tspan fill=\"#ff0000\">  </tspan>"));
+    }
+
+    #[test]
+    fn custom_palettes() {
+        assert!(Palette::new("x").is_err());
+        let palette = Palette::new(" #").unwrap();
+        assert_eq!(palette.glyphs(), &[' ', '#']);
+        assert_eq!("ascii".parse::<Palette>(), Ok(Palette::ascii()));
+        //evenly spaced from the brightest to the darkest pixel
+        assert_eq!(Palette::blocks().levels(0.0, 60.0), vec![60.0, 45.0, 30.0, 15.0, 0.0]);
+        assert_eq!(Palette::new(" #").unwrap().levels(120.0, 255.0), vec![255.0, 120.0]);
+    }
+
+    #[test]
+    fn streaming_downsample_averages_blocks() {
+        //4x2 image, each 2x2 block of the left and right halves averages to a known color
+        let img = image::RgbImage::from_fn(4, 2, |x, y| match (x < 2, (x + y) % 2 == 0) {
+            (true, true) => image::Rgb([0, 0, 0]),
+            (true, false) => image::Rgb([100, 100, 100]),
+            (false, _) => image::Rgb([10, 20, 30]),
+        });
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgb8(img).write_to(&mut png, image::ImageOutputFormat::Png).unwrap();
+
+        let decoder = image::codecs::png::PngDecoder::new(std::io::Cursor::new(&png)).unwrap();
+        let small = stream::downsample(decoder, 2, 1).unwrap();
+        assert_eq!(small.get_pixel(0, 0), &image::Rgb([50, 50, 50]));
+        assert_eq!(small.get_pixel(1, 0), &image::Rgb([10, 20, 30]));
+
+        //can't stream bigger than the source
+        let decoder = image::codecs::png::PngDecoder::new(std::io::Cursor::new(&png)).unwrap();
+        assert!(stream::downsample(decoder, 8, 1).is_err());
+    }
+
+    #[test]
+    fn render_file_matches_render_for_small_images() {
+        let renderer = AsciiRenderer::new().width(20);
+        assert_eq!(
+            renderer.render_file("simple_4_color_gray.png").unwrap(),
+            renderer.render(&image::open("simple_4_color_gray.png").unwrap())
+        );
+    }
+
+    #[test]
+    fn batch_output_paths_mirror_the_input_tree() {
+        use std::path::Path;
+        let out = batch::output_path(Path::new("scans/2021/a.png"), Path::new("scans"), Path::new("out"), batch::OutputFormat::Html);
+        assert_eq!(out, Path::new("out/2021/a.png.html"));
+        //images that only differ in their extension don't overwrite each other's art
+        let jpg = batch::output_path(Path::new("scans/2021/a.jpg"), Path::new("scans"), Path::new("out"), batch::OutputFormat::Html);
+        assert_ne!(out, jpg);
+        assert!(batch::is_batch("scans/**/*.png"));
+        assert!(!batch::is_batch("simple_4_color_gray.png"));
+        assert!(batch::is_supported_image(Path::new("a.JPG")));
+        assert!(!batch::is_supported_image(Path::new("notes.txt")));
+    }
+
+    #[test]
+    fn batch_carries_on_past_failures() {
+        let dir = std::env::temp_dir().join(format!("image_to_ascii_art_batch_{}", std::process::id()));
+        let (input, output) = (dir.join("in"), dir.join("out"));
+        fs::create_dir_all(input.join("nested")).unwrap();
+        fs::copy("simple_4_color_gray.png", input.join("good.png")).unwrap();
+        fs::copy("simple_4_color_gray.png", input.join("nested/also_good.png")).unwrap();
+        fs::write(input.join("broken.png"), b"not a png").unwrap();
+        fs::write(input.join("readme.txt"), b"skipped").unwrap();
+
+        let inputs = batch::collect_inputs(input.to_str().unwrap()).unwrap();
+        let (base, inputs) = (inputs.base, inputs.images);
+        assert_eq!(inputs.len(), 3);
+        let report = batch::convert_all(&inputs, &base, &output, &AsciiRenderer::new().width(10), batch::OutputFormat::Text);
+        assert_eq!(report.converted(), 2);
+        assert_eq!(report.failed().count(), 1);
+        assert!(output.join("good.png.txt").is_file());
+        assert!(output.join("nested/also_good.png.txt").is_file());
+        assert!(report.to_string().starts_with("converted 2 of 3 images, 1 failed\n"));
+        let json = report.to_json();
+        assert!(json.contains("\"total\": 3,"));
+        assert!(json.contains("broken.png\", \"output\": null, \"error\": \""));
+
+        //the glob finds the same images
+        let globbed = batch::collect_inputs(&format!("{}/**/*.png", input.display())).unwrap();
+        assert_eq!(globbed, batch::Inputs { base, images: inputs, unreadable: Vec::new() });
+
+        //a directory that can't be read is a failure, the rest of the walk carries on
+        let (mut inputs, mut unreadable) = (Vec::new(), Vec::new());
+        batch::walk(&dir.join("missing"), &mut inputs, &mut unreadable);
+        batch::walk(&input, &mut inputs, &mut unreadable);
+        assert_eq!((inputs.len(), unreadable.len()), (3, 1));
+        assert_eq!((unreadable[0].input.clone(), unreadable[0].output.clone()), (dir.join("missing"), None));
+        assert!(unreadable[0].error.is_some());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rasterized_art_looks_like_its_source() {
+        let img = image::open("simple_4_color_gray.png").unwrap();
+        let art = AsciiRenderer::new().width(40).render(&img);
+        let drawn = art.to_image();
+        assert_eq!((drawn.width(), drawn.height()), (art.width() as u32 * raster::CHAR_WIDTH, art.height() as u32 * raster::CHAR_HEIGHT));
+
+        let drawn = image::DynamicImage::ImageRgb8(drawn);
+        let mut inverted = img.clone();
+        inverted.invert();
+        let (same, opposite) = (raster::similarity(&drawn, &img), raster::similarity(&drawn, &inverted));
+        assert!(same > 0.75, "similarity to the source was only {}", same);
+        assert!(same > opposite);
+        assert_eq!(raster::similarity(&img, &img), 1.0);
+    }
+
+    #[test]
+    fn rasterize_follows_ansi_colors() {
+        let img = raster::rasterize("\x1b[2J\x1b[H\x1b[38;2;255;0;0m█\x1b[0m\x1b[44m \x1b[38;5;46m█\x1b[0m\n");
+        assert_eq!((img.width(), img.height()), (3 * raster::CHAR_WIDTH, raster::CHAR_HEIGHT));
+        assert_eq!(*img.get_pixel(0, 0), image::Rgb([255, 0, 0]));
+        assert_eq!(*img.get_pixel(raster::CHAR_WIDTH, 0), image::Rgb([0, 0, 238]));
+        assert_eq!(*img.get_pixel(2 * raster::CHAR_WIDTH, 0), image::Rgb([0, 255, 0]));
+
+        //plain text is black on white, and characters the font doesn't have still show up
+        let rows = raster::parse_ansi("ab\n\tc");
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].len(), 9);
+        assert!(rows.iter().flatten().all(|cell| cell.foreground == raster::DEFAULT_FOREGROUND));
+        assert!((0..raster::CHAR_HEIGHT).any(|y| raster::glyph_pixel('€', 0, y)));
+        assert!(!(0..raster::CHAR_HEIGHT).any(|y| (0..raster::CHAR_WIDTH).any(|x| raster::glyph_pixel('\u{2800}', x, y))));
+        assert!((0..raster::CHAR_HEIGHT).any(|y| (0..raster::CHAR_WIDTH).any(|x| raster::glyph_pixel('\u{28ff}', x, y))));
+    }
+
+    #[test]
+    fn preprocessing_runs_in_order() {
+        use image::GenericImageView;
+        use preprocess::{Flip, Pipeline};
+        //a 4x2 image with a dark pixel in the top left
+        let img = image::DynamicImage::ImageRgb8(image::RgbImage::from_fn(4, 2, |x, y| {
+            if (x, y) == (0, 0) {image::Rgb([10, 10, 10])} else {image::Rgb([200, 200, 200])}
+        }));
+
+        let rotated = Pipeline::new().rotate(90).unwrap().apply(img.clone()).into_rgb8();
+        assert_eq!(rotated.dimensions(), (2, 4));
+        assert_eq!(rotated.get_pixel(1, 0)[0], 10);
+        let flipped = Pipeline::new().flip(Flip::Horizontal).flip(Flip::Vertical).apply(img.clone()).into_rgb8();
+        assert_eq!(flipped.get_pixel(3, 1)[0], 10);
+        let cropped = Pipeline::new().crop(1, 0, 10, 10).unwrap().apply(img.clone());
+        assert_eq!(cropped.dimensions(), (3, 2));
+        let binary = Pipeline::new().threshold(100).apply(img.clone()).into_luma8();
+        assert!(binary.pixels().all(|p| p[0] == 0 || p[0] == 255));
+        assert!(Pipeline::new().rotate(45).is_err());
+        //steps added directly are checked the same way
+        assert!(Pipeline::new().then(preprocess::Step::Rotate(45)).is_err());
+        assert!(Pipeline::new().then(preprocess::Step::Crop { x: 0, y: 0, width: 0, height: 3 }).is_err());
+        assert_eq!(Pipeline::new().then(preprocess::Step::Rotate(180)).unwrap().steps(), &[preprocess::Step::Rotate(180)]);
+
+        //crop then rotate isn't the same as rotate then crop
+        let a = Pipeline::new().crop(0, 0, 1, 2).unwrap().rotate(90).unwrap().apply(img.clone());
+        let b = Pipeline::new().rotate(90).unwrap().crop(0, 0, 1, 2).unwrap().apply(img);
+        assert_eq!((a.dimensions(), b.dimensions()), ((2, 1), (1, 2)));
+    }
+
+    #[test]
+    fn auto_crop_removes_uniform_borders() {
+        use image::GenericImageView;
+        let img = image::DynamicImage::ImageRgb8(image::RgbImage::from_fn(10, 8, |x, y| {
+            if (3..6).contains(&x) && (2..4).contains(&y) {image::Rgb([0, 0, 0])} else {image::Rgb([250, 250, 250])}
+        }));
+        assert_eq!(preprocess::content_bounds(&img, 10), (3, 2, 3, 2));
+        assert_eq!(preprocess::Pipeline::new().auto_crop(10).apply(img).dimensions(), (3, 2));
+
+        //a solid image is left alone
+        let solid = image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(5, 5, image::Rgb([9, 9, 9])));
+        assert_eq!(preprocess::content_bounds(&solid, 0), (0, 0, 5, 5));
+
+        //from the command line, the flags build the pipeline in order
+        let args: Vec<String> = ["prog", "img.png", "--auto-crop=4", "--rotate=270", "--sharpen=1.5,3", "--blur=0.5"].iter().map(|s| s.to_string()).collect();
+        let config = Config::new(&args).unwrap();
+        assert_eq!(config.preprocess.steps(), &[
+            preprocess::Step::AutoCrop(4),
+            preprocess::Step::Rotate(270),
+            preprocess::Step::Sharpen { sigma: 1.5, threshold: 3 },
+            preprocess::Step::Blur(0.5),
+        ]);
+        let bad: Vec<String> = ["prog", "img.png", "--crop=1,2,3"].iter().map(|s| s.to_string()).collect();
+        assert!(Config::new(&bad).is_err());
+    }
+
+    #[test]
+    fn empty_crops_are_errors() {
+        let empty: Vec<String> = ["prog", "img.png", "--crop=0,0,0,0"].iter().map(|s| s.to_string()).collect();
+        assert!(Config::new(&empty).is_err());
+        assert!(preprocess::Pipeline::new().crop(0, 0, 5, 0).is_err());
+
+        //a crop outside the image leaves nothing to render
+        let outside: Vec<String> = ["prog", "img.png", "--crop=5000,5000,10,10"].iter().map(|s| s.to_string()).collect();
+        let renderer = Config::new(&outside).unwrap().renderer();
+        assert!(renderer.render_file("simple_4_color_gray.png").is_err());
+        assert!(renderer.render_bytes(&fs::read("simple_4_color_gray.png").unwrap()).is_err());
+        assert_eq!(renderer.render(&image::open("simple_4_color_gray.png").unwrap()).as_str(), "");
+    }
+
+    #[test]
+    fn gradients_that_dont_start_at_black_use_every_level() {
+        //a gray to white gradient, its darkest pixel is far from 0
+        let img = image::RgbImage::from_fn(64, 8, |x, _| {
+            let v = (120 + x * 135 / 63) as u8;
+            image::Rgb([v, v, v])
+        });
+        for method in [Dither::None, Dither::FloydSteinberg, Dither::Atkinson, Dither::Bayer(4)].iter() {
+            let art = gen_ascii(&img, &Palette::blocks(), *method);
+            let mut glyphs: Vec<char> = art.chars().filter(|c| *c != '\n').collect();
+            glyphs.sort_unstable();
+            glyphs.dedup();
+            assert!(glyphs.len() > 1, "{} drew a single glyph", method);
+        }
+    }
+
+    #[test]
+    fn error_diffusion_keeps_solid_images_solid() {
+        let img = image::RgbImage::from_pixel(10, 4, image::Rgb([255, 255, 255]));
+        let art = gen_ascii(&img, &Palette::blocks(), Dither::FloydSteinberg);
+        assert!(art.lines().all(|line| line.chars().all(|c| c == ' ')));
+    }
+}
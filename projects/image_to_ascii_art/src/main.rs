@@ -1,8 +1,8 @@
 use std::env;           //the library that will allow us to do stuff and stuff
 use std::process;       //allows for some better error handling
 
-mod lib;
-use crate::lib::Config;
+use image_to_ascii_art as lib;
+use lib::Config;
 
 fn main() {
     let args: Vec<String> = env::args().collect(); //read the argument values, and collect them into a string vector
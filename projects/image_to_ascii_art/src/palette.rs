@@ -0,0 +1,64 @@
+//! the characters luminance is drawn with
+
+use std::str::FromStr;
+
+/// a set of characters ordered from the brightest to the darkest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    glyphs: Vec<char>,
+}
+impl Palette {
+    /**
+     * creates a palette from its characters, brightest first, there must be at least 2
+     */
+    pub fn new(glyphs: &str) -> Result<Palette, &'static str> {
+        let glyphs: Vec<char> = glyphs.chars().collect();
+        if glyphs.len() < 2 {
+            return Err("a palette needs at least 2 characters");
+        }
+        Ok(Palette { glyphs })
+    }
+
+    /// shaded blocks, the original palette
+    pub fn blocks() -> Palette {
+        Palette { glyphs: vec![' ', '░', '▒', '▓', '█'] }
+    }
+
+    /// plain ASCII, for places that can't show the block characters
+    pub fn ascii() -> Palette {
+        Palette { glyphs: " .:-=+*#%@".chars().collect() }
+    }
+
+    pub fn glyphs(&self) -> &[char] {
+        &self.glyphs
+    }
+
+    /**
+     * the luminance every character stands for, in an image whose luminance goes from min to max
+     *
+     * the levels are evenly spaced from max for the first character down to min for the last
+     */
+    pub fn levels(&self, min: f32, max: f32) -> Vec<f32> {
+        let steps = (self.glyphs.len() - 1) as f32;
+        (0..self.glyphs.len()).map(|k| max - (max - min) * k as f32 / steps).collect()
+    }
+}
+impl Default for Palette {
+    fn default() -> Self {
+        Palette::blocks()
+    }
+}
+impl FromStr for Palette {
+    type Err = &'static str;
+
+    /**
+     * parses a palette from its name
+     */
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "blocks" => Ok(Palette::blocks()),
+            "ascii" => Ok(Palette::ascii()),
+            _ => Err("unknown palette, expected one of: blocks, ascii"),
+        }
+    }
+}
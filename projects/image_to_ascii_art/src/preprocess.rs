@@ -0,0 +1,198 @@
+//! transforms applied to an image before it's rendered, run in the order they were added
+//!
+//! ```no_run
+//! use image_to_ascii_art::preprocess::{Flip, Pipeline};
+//! use image_to_ascii_art::AsciiRenderer;
+//!
+//! let pipeline = Pipeline::new().auto_crop(8).rotate(90).unwrap().flip(Flip::Horizontal).sharpen(1.0, 2);
+//! let art = AsciiRenderer::new().preprocess(pipeline).render_file("simple_4_color_gray.png").unwrap();
+//! ```
+
+use image::{DynamicImage, GenericImageView};
+use std::str::FromStr;
+
+/// which way to mirror an image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flip {
+    /// left to right
+    Horizontal,
+    /// top to bottom
+    Vertical,
+}
+impl FromStr for Flip {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "horizontal" | "h" => Ok(Flip::Horizontal),
+            "vertical" | "v" => Ok(Flip::Vertical),
+            _ => Err("unknown flip, expected one of: horizontal, vertical"),
+        }
+    }
+}
+
+/// one transform of the pipeline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Step {
+    /// keeps the rectangle with its top left corner at (x, y), clipped to the image, so nothing is left of the image
+    /// when the rectangle is outside of it
+    Crop { x: u32, y: u32, width: u32, height: u32 },
+    /// turns the image clockwise by 90, 180 or 270 degrees
+    Rotate(u32),
+    Flip(Flip),
+    /// makes pixels with a luminance below the threshold black, and the rest white
+    Threshold(u8),
+    /// gaussian blur with the given sigma
+    Blur(f32),
+    /// unsharp mask, sharpens edges whose difference is more than the threshold
+    Sharpen { sigma: f32, threshold: i32 },
+    /// crops away borders the same color as the top left corner, give or take the tolerance
+    AutoCrop(u8),
+}
+impl Step {
+    /**
+     * whether the step can be run, crops can't be empty and only quarter turns can be rotated by
+     */
+    pub fn validate(&self) -> Result<(), &'static str> {
+        match *self {
+            Step::Crop { width: 0, .. } | Step::Crop { height: 0, .. } => Err("crop width and height must be more than 0"),
+            Step::Rotate(90 | 180 | 270) => Ok(()),
+            Step::Rotate(_) => Err("rotation must be 90, 180 or 270 degrees"),
+            _ => Ok(()),
+        }
+    }
+
+    /**
+     * applies the transform to an image, the step must have been validated
+     */
+    pub(crate) fn apply(&self, img: DynamicImage) -> DynamicImage {
+        match *self {
+            Step::Crop { x, y, width, height } => {
+                let x = x.min(img.width());
+                let y = y.min(img.height());
+                img.crop_imm(x, y, width.min(img.width() - x), height.min(img.height() - y))
+            }
+            Step::Rotate(90) => img.rotate90(),
+            Step::Rotate(180) => img.rotate180(),
+            Step::Rotate(270) => img.rotate270(),
+            Step::Rotate(_) => unreachable!("a pipeline only holds validated steps"),
+            Step::Flip(Flip::Horizontal) => img.fliph(),
+            Step::Flip(Flip::Vertical) => img.flipv(),
+            Step::Threshold(threshold) => {
+                let mut gray = img.into_luma8();
+                for pixel in gray.pixels_mut() {
+                    pixel[0] = if pixel[0] < threshold {0} else {255};
+                }
+                DynamicImage::ImageLuma8(gray)
+            }
+            Step::Blur(sigma) => img.blur(sigma),
+            Step::Sharpen { sigma, threshold } => img.unsharpen(sigma, threshold),
+            Step::AutoCrop(tolerance) => {
+                let (x, y, width, height) = content_bounds(&img, tolerance);
+                img.crop_imm(x, y, width, height)
+            }
+        }
+    }
+}
+
+/**
+ * finds the rectangle, in (x, y, width, height), left once borders the color of the top left corner are cropped away
+ *
+ * an image that's all one color is left as it is
+ */
+pub fn content_bounds(img: &DynamicImage, tolerance: u8) -> (u32, u32, u32, u32) {
+    let (width, height) = img.dimensions();
+    if width == 0 || height == 0 {
+        return (0, 0, width, height);
+    }
+    let background = img.get_pixel(0, 0);
+    let is_background = |x: u32, y: u32| {
+        let pixel = img.get_pixel(x, y);
+        pixel.0.iter().zip(background.0.iter()).all(|(a, b)| (*a as i32 - *b as i32).unsigned_abs() <= tolerance as u32)
+    };
+
+    let (mut left, mut top, mut right, mut bottom) = (width, height, 0, 0);
+    for y in 0..height {
+        for x in 0..width {
+            if !is_background(x, y) {
+                left = left.min(x);
+                top = top.min(y);
+                right = right.max(x);
+                bottom = bottom.max(y);
+            }
+        }
+    }
+    if left > right {
+        return (0, 0, width, height);
+    }
+    (left, top, right - left + 1, bottom - top + 1)
+}
+
+/// transforms to run on an image before rendering it, in order
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Pipeline {
+    steps: Vec<Step>,
+}
+impl Pipeline {
+    /// a pipeline that leaves images as they are
+    pub fn new() -> Pipeline {
+        Pipeline { steps: Vec::new() }
+    }
+
+    /// adds any step to the end of the pipeline, it's an error for the step not to be valid, see `Step::validate`
+    pub fn then(self, step: Step) -> Result<Self, &'static str> {
+        step.validate()?;
+        Ok(self.push(step))
+    }
+
+    /// adds a step that's always valid
+    fn push(mut self, step: Step) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// keeps a rectangle of the image, it can't be empty
+    pub fn crop(self, x: u32, y: u32, width: u32, height: u32) -> Result<Self, &'static str> {
+        self.then(Step::Crop { x, y, width, height })
+    }
+
+    /// turns the image clockwise, only 90, 180 and 270 degrees can be used
+    pub fn rotate(self, degrees: u32) -> Result<Self, &'static str> {
+        self.then(Step::Rotate(degrees))
+    }
+
+    pub fn flip(self, flip: Flip) -> Self {
+        self.push(Step::Flip(flip))
+    }
+
+    pub fn threshold(self, threshold: u8) -> Self {
+        self.push(Step::Threshold(threshold))
+    }
+
+    pub fn blur(self, sigma: f32) -> Self {
+        self.push(Step::Blur(sigma))
+    }
+
+    pub fn sharpen(self, sigma: f32, threshold: i32) -> Self {
+        self.push(Step::Sharpen { sigma, threshold })
+    }
+
+    pub fn auto_crop(self, tolerance: u8) -> Self {
+        self.push(Step::AutoCrop(tolerance))
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /**
+     * runs every step on an image
+     */
+    pub fn apply(&self, img: DynamicImage) -> DynamicImage {
+        self.steps.iter().fold(img, |img, step| step.apply(img))
+    }
+}
@@ -0,0 +1,317 @@
+//! turning text back into an image, drawing rendered art (with its ANSI colors) in an embedded bitmap font
+//!
+//! used to preview art as a PNG, and to check how close rendered art comes to the image it was rendered from
+
+use crate::braille;
+use image::{Rgb, RgbImage};
+
+/// width and height, in pixels, of the cell every character is drawn in, 2 times taller than wide like a terminal
+pub const CHAR_WIDTH: u32 = 6;
+pub const CHAR_HEIGHT: u32 = 12;
+
+/// size of the glyphs in the font, and how far down the cell they start
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 8;
+const GLYPH_TOP: u32 = 2;
+
+/// colors used until an ANSI code sets one, dark text on a light background, the way the palettes are ordered
+pub const DEFAULT_FOREGROUND: Rgb<u8> = Rgb([0, 0, 0]);
+pub const DEFAULT_BACKGROUND: Rgb<u8> = Rgb([255, 255, 255]);
+
+/// the 16 basic ANSI colors, in the xterm shades
+const BASIC_COLORS: [[u8; 3]; 16] = [
+    [0, 0, 0], [205, 0, 0], [0, 205, 0], [205, 205, 0], [0, 0, 238], [205, 0, 205], [0, 205, 205], [229, 229, 229],
+    [127, 127, 127], [255, 0, 0], [0, 255, 0], [255, 255, 0], [92, 92, 255], [255, 0, 255], [0, 255, 255], [255, 255, 255],
+];
+
+/// 5x8 font for printable ASCII, starting at ' ', every row is 5 bits with the leftmost pixel in the highest bit.
+/// the last row is only used by descenders
+const FONT: [[u8; 8]; 95] = [
+    [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000], // ' '
+    [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100, 0b00000], // !
+    [0b01010, 0b01010, 0b01010, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000], // "
+    [0b01010, 0b01010, 0b11111, 0b01010, 0b11111, 0b01010, 0b01010, 0b00000], // #
+    [0b00100, 0b01111, 0b10100, 0b01110, 0b00101, 0b11110, 0b00100, 0b00000], // $
+    [0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011, 0b00000], // %
+    [0b01100, 0b10010, 0b10100, 0b01000, 0b10101, 0b10010, 0b01101, 0b00000], // &
+    [0b01100, 0b00100, 0b01000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000], // '
+    [0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010, 0b00000], // (
+    [0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000, 0b00000], // )
+    [0b00000, 0b00100, 0b10101, 0b01110, 0b10101, 0b00100, 0b00000, 0b00000], // *
+    [0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000, 0b00000], // +
+    [0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000, 0b00000], // ,
+    [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000, 0b00000], // -
+    [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100, 0b00000], // .
+    [0b00000, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000, 0b00000], // /
+    [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110, 0b00000], // 0
+    [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110, 0b00000], // 1
+    [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111, 0b00000], // 2
+    [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110, 0b00000], // 3
+    [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010, 0b00000], // 4
+    [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110, 0b00000], // 5
+    [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110, 0b00000], // 6
+    [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00000], // 7
+    [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110, 0b00000], // 8
+    [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100, 0b00000], // 9
+    [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000, 0b00000], // :
+    [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b00100, 0b01000, 0b00000], // ;
+    [0b00010, 0b00100, 0b01000, 0b10000, 0b01000, 0b00100, 0b00010, 0b00000], // <
+    [0b00000, 0b00000, 0b11111, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000], // =
+    [0b01000, 0b00100, 0b00010, 0b00001, 0b00010, 0b00100, 0b01000, 0b00000], // >
+    [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100, 0b00000], // ?
+    [0b01110, 0b10001, 0b00001, 0b01101, 0b10101, 0b10101, 0b01110, 0b00000], // @
+    [0b01110, 0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b00000], // A
+    [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110, 0b00000], // B
+    [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110, 0b00000], // C
+    [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100, 0b00000], // D
+    [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111, 0b00000], // E
+    [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000, 0b00000], // F
+    [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111, 0b00000], // G
+    [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001, 0b00000], // H
+    [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110, 0b00000], // I
+    [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100, 0b00000], // J
+    [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001, 0b00000], // K
+    [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111, 0b00000], // L
+    [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001, 0b00000], // M
+    [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001, 0b00000], // N
+    [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110, 0b00000], // O
+    [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000, 0b00000], // P
+    [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101, 0b00000], // Q
+    [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001, 0b00000], // R
+    [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110, 0b00000], // S
+    [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000], // T
+    [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110, 0b00000], // U
+    [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00000], // V
+    [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010, 0b00000], // W
+    [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001, 0b00000], // X
+    [0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100, 0b00000], // Y
+    [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111, 0b00000], // Z
+    [0b01110, 0b01000, 0b01000, 0b01000, 0b01000, 0b01000, 0b01110, 0b00000], // [
+    [0b00000, 0b10000, 0b01000, 0b00100, 0b00010, 0b00001, 0b00000, 0b00000], // \
+    [0b01110, 0b00010, 0b00010, 0b00010, 0b00010, 0b00010, 0b01110, 0b00000], // ]
+    [0b00100, 0b01010, 0b10001, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000], // ^
+    [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111, 0b00000], // _
+    [0b01000, 0b00100, 0b00010, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000], // `
+    [0b00000, 0b00000, 0b01110, 0b00001, 0b01111, 0b10001, 0b01111, 0b00000], // a
+    [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b11110, 0b00000], // b
+    [0b00000, 0b00000, 0b01110, 0b10000, 0b10000, 0b10001, 0b01110, 0b00000], // c
+    [0b00001, 0b00001, 0b01101, 0b10011, 0b10001, 0b10001, 0b01111, 0b00000], // d
+    [0b00000, 0b00000, 0b01110, 0b10001, 0b11111, 0b10000, 0b01110, 0b00000], // e
+    [0b00110, 0b01001, 0b01000, 0b11100, 0b01000, 0b01000, 0b01000, 0b00000], // f
+    [0b00000, 0b00000, 0b01111, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110], // g
+    [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001, 0b00000], // h
+    [0b00100, 0b00000, 0b01100, 0b00100, 0b00100, 0b00100, 0b01110, 0b00000], // i
+    [0b00010, 0b00000, 0b00110, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100], // j
+    [0b10000, 0b10000, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b00000], // k
+    [0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110, 0b00000], // l
+    [0b00000, 0b00000, 0b11010, 0b10101, 0b10101, 0b10001, 0b10001, 0b00000], // m
+    [0b00000, 0b00000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001, 0b00000], // n
+    [0b00000, 0b00000, 0b01110, 0b10001, 0b10001, 0b10001, 0b01110, 0b00000], // o
+    [0b00000, 0b00000, 0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000], // p
+    [0b00000, 0b00000, 0b01101, 0b10011, 0b10001, 0b01111, 0b00001, 0b00001], // q
+    [0b00000, 0b00000, 0b10110, 0b11001, 0b10000, 0b10000, 0b10000, 0b00000], // r
+    [0b00000, 0b00000, 0b01110, 0b10000, 0b01110, 0b00001, 0b11110, 0b00000], // s
+    [0b01000, 0b01000, 0b11100, 0b01000, 0b01000, 0b01001, 0b00110, 0b00000], // t
+    [0b00000, 0b00000, 0b10001, 0b10001, 0b10001, 0b10011, 0b01101, 0b00000], // u
+    [0b00000, 0b00000, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00000], // v
+    [0b00000, 0b00000, 0b10001, 0b10001, 0b10101, 0b10101, 0b01010, 0b00000], // w
+    [0b00000, 0b00000, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b00000], // x
+    [0b00000, 0b00000, 0b10001, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110], // y
+    [0b00000, 0b00000, 0b11111, 0b00010, 0b00100, 0b01000, 0b11111, 0b00000], // z
+    [0b00010, 0b00100, 0b00100, 0b01000, 0b00100, 0b00100, 0b00010, 0b00000], // {
+    [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100], // |
+    [0b01000, 0b00100, 0b00100, 0b00010, 0b00100, 0b00100, 0b01000, 0b00000], // }
+    [0b00000, 0b00000, 0b01000, 0b10101, 0b00010, 0b00000, 0b00000, 0b00000], // ~
+];
+
+/// one character of text, with the colors it's drawn in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cell {
+    pub c: char,
+    pub foreground: Rgb<u8>,
+    pub background: Rgb<u8>,
+}
+
+/**
+ * splits text into rows of cells, following the ANSI color codes in it
+ *
+ * 24 bit, 256 color and the 16 basic colors are understood, for the foreground and background. every other
+ * escape code (moving the cursor, clearing the screen) is skipped
+ */
+pub fn parse_ansi(text: &str) -> Vec<Vec<Cell>> {
+    let mut rows = vec![Vec::new()];
+    let (mut foreground, mut background) = (DEFAULT_FOREGROUND, DEFAULT_BACKGROUND);
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => {
+                if chars.peek() != Some(&'[') {
+                    chars.next();
+                    continue;
+                }
+                chars.next();
+                //parameters run until the final byte, which is between @ and ~
+                let mut params = String::new();
+                let mut last = None;
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        last = Some(c);
+                        break;
+                    }
+                    params.push(c);
+                }
+                if last == Some('m') {
+                    apply_sgr(&params, &mut foreground, &mut background);
+                }
+            }
+            '\n' => rows.push(Vec::new()),
+            '\r' => {}
+            '\t' => {
+                let row = rows.last_mut().unwrap();
+                while {
+                    row.push(Cell { c: ' ', foreground, background });
+                    row.len() % 8 != 0
+                } {}
+            }
+            c => rows.last_mut().unwrap().push(Cell { c, foreground, background }),
+        }
+    }
+    //text ending in a newline doesn't have another row after it
+    if rows.len() > 1 && rows.last().map_or(false, |row| row.is_empty()) {
+        rows.pop();
+    }
+    rows
+}
+
+/**
+ * applies the parameters of a "select graphic rendition" code, the one that sets colors
+ */
+fn apply_sgr(params: &str, foreground: &mut Rgb<u8>, background: &mut Rgb<u8>) {
+    let codes: Vec<u32> = params.split(';').map(|p| p.parse().unwrap_or(0)).collect();
+    let mut i = 0;
+    while i < codes.len() {
+        match codes[i] {
+            0 => {
+                *foreground = DEFAULT_FOREGROUND;
+                *background = DEFAULT_BACKGROUND;
+            }
+            code @ 30..=37 => *foreground = Rgb(BASIC_COLORS[(code - 30) as usize]),
+            code @ 90..=97 => *foreground = Rgb(BASIC_COLORS[(code - 90 + 8) as usize]),
+            code @ 40..=47 => *background = Rgb(BASIC_COLORS[(code - 40) as usize]),
+            code @ 100..=107 => *background = Rgb(BASIC_COLORS[(code - 100 + 8) as usize]),
+            39 => *foreground = DEFAULT_FOREGROUND,
+            49 => *background = DEFAULT_BACKGROUND,
+            code @ (38 | 48) => {
+                let target = if code == 38 {&mut *foreground} else {&mut *background};
+                match codes.get(i + 1) {
+                    Some(2) if i + 4 < codes.len() => {
+                        let channel = |n: usize| codes[i + n].min(255) as u8;
+                        *target = Rgb([channel(2), channel(3), channel(4)]);
+                        i += 4;
+                    }
+                    Some(5) if i + 2 < codes.len() => {
+                        *target = color_256(codes[i + 2]);
+                        i += 2;
+                    }
+                    _ => {}
+                }
+            }
+            //bold, underline and the rest don't change the colors
+            _ => {}
+        }
+        i += 1;
+    }
+}
+
+/**
+ * the color of one of the 256 xterm colors, the basic 16, then a 6x6x6 cube, then 24 grays
+ */
+fn color_256(index: u32) -> Rgb<u8> {
+    let cube = |level: u32| if level == 0 {0} else {(55 + level * 40) as u8};
+    match index {
+        0..=15 => Rgb(BASIC_COLORS[index as usize]),
+        16..=231 => {
+            let index = index - 16;
+            Rgb([cube(index / 36), cube(index / 6 % 6), cube(index % 6)])
+        }
+        _ => {
+            let gray = (8 + (index.min(255) - 232) * 10) as u8;
+            Rgb([gray, gray, gray])
+        }
+    }
+}
+
+/**
+ * whether the pixel at (x, y) of a character's cell is drawn in the foreground color
+ */
+pub fn glyph_pixel(c: char, x: u32, y: u32) -> bool {
+    match c {
+        //shaded blocks are patterns covering a quarter, half, three quarters and all of the cell
+        '░' => x % 2 == 0 && y % 2 == 0,
+        '▒' => (x + y) % 2 == 0,
+        '▓' => x % 2 == 0 || y % 2 == 0,
+        '█' => true,
+        ' '..='~' => {
+            if x >= GLYPH_WIDTH || !(GLYPH_TOP..GLYPH_TOP + GLYPH_HEIGHT).contains(&y) {
+                return false;
+            }
+            let row = FONT[(c as u32 - ' ' as u32) as usize][(y - GLYPH_TOP) as usize];
+            row & (1 << (GLYPH_WIDTH - 1 - x)) != 0
+        }
+        c if (braille::BRAILLE_BASE..braille::BRAILLE_BASE + 0x100).contains(&(c as u32)) => {
+            //the cell is split into a 2x4 grid of dots, each dot is 2x2 pixels in the middle of its part of the grid
+            let (dot_width, dot_height) = (CHAR_WIDTH / braille::CELL_WIDTH, CHAR_HEIGHT / braille::CELL_HEIGHT);
+            let (dot_x, dot_y) = (x / dot_width, y / dot_height);
+            let (inner_x, inner_y) = (x % dot_width, y % dot_height);
+            let raised = (c as u32 - braille::BRAILLE_BASE) & braille::DOT_BITS[dot_y as usize][dot_x as usize] != 0;
+            raised && inner_x < 2 && (1..3).contains(&inner_y)
+        }
+        //anything the font doesn't have is drawn as a hollow box
+        _ => {
+            let (left, right, top, bottom) = (0, GLYPH_WIDTH - 1, GLYPH_TOP, GLYPH_TOP + GLYPH_HEIGHT - 2);
+            (x == left || x == right) && (top..=bottom).contains(&y) || (y == top || y == bottom) && (left..=right).contains(&x)
+        }
+    }
+}
+
+/**
+ * draws text into an image, every character takes up `CHAR_WIDTH`x`CHAR_HEIGHT` pixels
+ *
+ * ANSI color codes in the text are followed, text without any is drawn in black on white
+ */
+pub fn rasterize(text: &str) -> RgbImage {
+    let rows = parse_ansi(text);
+    let cols = rows.iter().map(|row| row.len()).max().unwrap_or(0) as u32;
+    let mut img = RgbImage::from_pixel(cols * CHAR_WIDTH, rows.len() as u32 * CHAR_HEIGHT, DEFAULT_BACKGROUND);
+    for (row, cells) in rows.iter().enumerate() {
+        for (col, cell) in cells.iter().enumerate() {
+            let (left, top) = (col as u32 * CHAR_WIDTH, row as u32 * CHAR_HEIGHT);
+            for y in 0..CHAR_HEIGHT {
+                for x in 0..CHAR_WIDTH {
+                    let color = if glyph_pixel(cell.c, x, y) {cell.foreground} else {cell.background};
+                    img.put_pixel(left + x, top + y, color);
+                }
+            }
+        }
+    }
+    img
+}
+
+/**
+ * how alike two images look, from 0 (black against white) to 1 (the same)
+ *
+ * both are shrunk to the smaller of their sizes and compared by the average difference in luminance,
+ * shrinking blurs away the pattern of the glyphs, so art can be compared to the image it was rendered from
+ */
+pub fn similarity(a: &image::DynamicImage, b: &image::DynamicImage) -> f32 {
+    use image::GenericImageView;
+    let width = a.width().min(b.width());
+    let height = a.height().min(b.height());
+    if width == 0 || height == 0 {
+        return if a.dimensions() == b.dimensions() {1.0} else {0.0};
+    }
+    let shrink = |img: &image::DynamicImage| img.resize_exact(width, height, image::imageops::Triangle).into_luma8();
+    let (a, b) = (shrink(a), shrink(b));
+    let difference: u64 = a.pixels().zip(b.pixels()).map(|(a, b)| (a[0] as i32 - b[0] as i32).unsigned_abs() as u64).sum();
+    1.0 - difference as f32 / (width as u64 * height as u64 * 255) as f32
+}
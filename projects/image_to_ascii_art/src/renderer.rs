@@ -0,0 +1,269 @@
+//! the library API, a builder for rendering images that are already in memory
+//!
+//! ```no_run
+//! use image_to_ascii_art::{AsciiRenderer, Palette};
+//!
+//! let img = image::open("simple_4_color_gray.png").unwrap();
+//! let art = AsciiRenderer::new().width(40).palette(Palette::ascii()).render(&img);
+//! println!("{}", art);
+//! ```
+
+use crate::preprocess::Pipeline;
+use crate::{braille, edge, export, raster, stream, terminal, Dither, Mode, Palette};
+use image::GenericImageView;
+use std::fmt;
+use std::path::Path;
+
+/// width of the art in characters, when none is given
+pub const DEFAULT_WIDTH: u32 = 80;
+
+/// renders images as text, built up one setting at a time
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsciiRenderer {
+    width: u32,
+    height: u32,
+    cell_aspect: f32,
+    mode: Mode,
+    palette: Palette,
+    dither: Dither,
+    threshold: Option<f32>,
+    edge_threshold: f32,
+    preprocess: Pipeline,
+}
+impl Default for AsciiRenderer {
+    fn default() -> Self {
+        AsciiRenderer::new()
+    }
+}
+impl AsciiRenderer {
+    /**
+     * a renderer with the default settings, 80 columns of shaded blocks with no dithering
+     */
+    pub fn new() -> AsciiRenderer {
+        AsciiRenderer {
+            width: DEFAULT_WIDTH,
+            height: 0,
+            cell_aspect: terminal::DEFAULT_CELL_ASPECT,
+            mode: Mode::default(),
+            palette: Palette::default(),
+            dither: Dither::default(),
+            threshold: None,
+            edge_threshold: edge::DEFAULT_EDGE_THRESHOLD,
+            preprocess: Pipeline::new(),
+        }
+    }
+
+    /// the width of the art in characters, 0 uses the default
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// the most rows the art can take up, the art gets narrower to fit, 0 doesn't limit it
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// how many times taller than wide a character is where the art will be shown
+    pub fn cell_aspect(mut self, cell_aspect: f32) -> Self {
+        self.cell_aspect = cell_aspect;
+        self
+    }
+
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// the characters used by the block and edge modes
+    pub fn palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    pub fn dither(mut self, dither: Dither) -> Self {
+        self.dither = dither;
+        self
+    }
+
+    /// the luminance below which the braille mode raises a dot, None uses the middle of the image's luminance
+    pub fn threshold(mut self, threshold: Option<f32>) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// the fraction of the strongest gradient the edge mode needs to draw an edge
+    pub fn edge_threshold(mut self, edge_threshold: f32) -> Self {
+        self.edge_threshold = edge_threshold;
+        self
+    }
+
+    /// transforms run on the image before it's resized and rendered
+    pub fn preprocess(mut self, preprocess: Pipeline) -> Self {
+        self.preprocess = preprocess;
+        self
+    }
+
+    /**
+     * the size of the art in (columns, rows) for an image of the given size
+     */
+    pub fn grid_size(&self, img_width: u32, img_height: u32) -> (u32, u32) {
+        let max_cols = if self.width == 0 {DEFAULT_WIDTH} else {self.width};
+        let max_rows = if self.height == 0 {None} else {Some(self.height)};
+        terminal::fit(img_width, img_height, max_cols, max_rows, self.cell_aspect)
+    }
+
+    /**
+     * the number of pixels the mode draws in the grid, for an image of the given size
+     */
+    pub fn pixel_size(&self, img_width: u32, img_height: u32) -> (u32, u32) {
+        let (cols, rows) = self.grid_size(img_width, img_height);
+        match self.mode {
+            Mode::Blocks | Mode::Edges => (cols, rows),
+            //a braille character is 2x4 pixels
+            Mode::Braille => (cols * braille::CELL_WIDTH, rows * braille::CELL_HEIGHT),
+        }
+    }
+
+    /**
+     * resizes an image to the number of pixels the mode draws in the grid
+     */
+    pub fn resize(&self, img: &image::DynamicImage) -> image::RgbImage {
+        let (width, height) = self.pixel_size(img.width(), img.height());
+
+        //the image is stretched to the grid rather than cropped, the grid already has the image's shape once the
+        //height of the characters is taken into account
+        img.resize_exact(width, height, image::imageops::Gaussian).into_rgb8()
+    }
+
+    /**
+     * renders an image that was already resized with `resize`, as plain text
+     */
+    pub fn render_resized(&self, img: &image::RgbImage) -> String {
+        match self.mode {
+            Mode::Blocks => crate::gen_ascii(img, &self.palette, self.dither),
+            Mode::Braille => braille::gen_braille(img, self.dither, self.threshold),
+            Mode::Edges => edge::gen_edges(img, &self.palette, self.dither, self.edge_threshold),
+        }
+    }
+
+    /**
+     * renders an image, preprocessing it first
+     *
+     * the art is empty when nothing is left of the image, like when it's cropped outside of its bounds,
+     * `render_file` and `render_bytes` return an error instead
+     */
+    pub fn render(&self, img: &image::DynamicImage) -> AsciiArt {
+        self.try_render(img).unwrap_or_else(|_| AsciiArt { text: String::new(), colors: Vec::new() })
+    }
+
+    /**
+     * renders an image, preprocessing it first, it's an error for nothing to be left of the image to render
+     */
+    fn try_render(&self, img: &image::DynamicImage) -> image::ImageResult<AsciiArt> {
+        let preprocessed;
+        let img = if self.preprocess.is_empty() {
+            img
+        } else {
+            preprocessed = self.preprocess.apply(img.clone());
+            &preprocessed
+        };
+        if img.width() == 0 || img.height() == 0 {
+            let message = "nothing is left of the image to render, is the crop outside of it?";
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, message).into());
+        }
+        let resized = self.resize(img);
+        let text = self.render_resized(&resized);
+        let colors = export::cell_colors(&text, &resized);
+        Ok(AsciiArt { text, colors })
+    }
+
+    /**
+     * renders an image file
+     *
+     * images with more than 16 megapixels, in formats that can be decoded a row at a time, are streamed
+     * straight into the size of the art instead of being decoded whole. streamed images are downsampled
+     * by averaging, so they can come out slightly different than if they were decoded whole. images are
+     * never streamed when there are preprocessing steps, those need the whole image
+     */
+    pub fn render_file<P: AsRef<Path>>(&self, path: P) -> image::ImageResult<AsciiArt> {
+        let path = path.as_ref();
+        let ((width, height), streamable) = stream::probe(path)?;
+        let (out_width, out_height) = self.pixel_size(width, height);
+
+        let huge = width as u64 * height as u64 > stream::STREAMING_THRESHOLD;
+        if !(huge && streamable && self.preprocess.is_empty() && out_width <= width && out_height <= height) {
+            return self.try_render(&image::open(path)?);
+        }
+
+        let resized = stream::open_downsampled(path, out_width, out_height)?;
+        let text = self.render_resized(&resized);
+        let colors = export::cell_colors(&text, &resized);
+        Ok(AsciiArt { text, colors })
+    }
+
+    /**
+     * renders an encoded image, in any format the image crate can guess from its bytes
+     */
+    pub fn render_bytes(&self, bytes: &[u8]) -> image::ImageResult<AsciiArt> {
+        self.try_render(&image::load_from_memory(bytes)?)
+    }
+}
+
+/// rendered art, along with the color of every character
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsciiArt {
+    text: String,
+    colors: Vec<Vec<image::Rgb<u8>>>,
+}
+impl AsciiArt {
+    /// the art as plain text, one line per row
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn into_string(self) -> String {
+        self.text
+    }
+
+    /// the color of every character, by row then column
+    pub fn colors(&self) -> &[Vec<image::Rgb<u8>>] {
+        &self.colors
+    }
+
+    /// the number of columns
+    pub fn width(&self) -> usize {
+        self.text.lines().map(|l| l.chars().count()).max().unwrap_or(0)
+    }
+
+    /// the number of rows
+    pub fn height(&self) -> usize {
+        self.text.lines().count()
+    }
+
+    /// the art with 24 bit ANSI color codes, for terminals
+    pub fn to_ansi(&self) -> String {
+        export::to_ansi(&self.text, &self.colors)
+    }
+
+    /// the art as a self-contained HTML page
+    pub fn to_html(&self, title: &str) -> String {
+        export::to_html(&self.text, &self.colors, title)
+    }
+
+    /// the art as an SVG
+    pub fn to_svg(&self) -> String {
+        export::to_svg(&self.text, &self.colors)
+    }
+
+    /// the art drawn in black on white with the embedded bitmap font, use `raster::rasterize(&art.to_ansi())` for color
+    pub fn to_image(&self) -> image::RgbImage {
+        raster::rasterize(&self.text)
+    }
+}
+impl fmt::Display for AsciiArt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
@@ -0,0 +1,105 @@
+//! streaming downsampling for images too big to comfortably hold in memory
+//!
+//! the decoder is read one row at a time and every row is added into a grid the size of the output,
+//! so the full resolution image never needs to be held at once. how much the decoder itself holds
+//! depends on the format, PNG, BMP, PNM and farbfeld are decoded row by row
+
+use image::codecs::{bmp, farbfeld, png, pnm};
+use image::{ColorType, ImageDecoder, ImageError, ImageFormat, ImageResult};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// images with more pixels than this are streamed by `AsciiRenderer::render_file`, 16 megapixels
+pub const STREAMING_THRESHOLD: u64 = 4096 * 4096;
+
+/**
+ * the size of an image file and whether it can be streamed, without decoding it
+ */
+pub fn probe(path: &Path) -> ImageResult<((u32, u32), bool)> {
+    let reader = image::io::Reader::open(path)?.with_guessed_format()?;
+    let streamable = matches!(
+        reader.format(),
+        Some(ImageFormat::Png) | Some(ImageFormat::Bmp) | Some(ImageFormat::Pnm) | Some(ImageFormat::Farbfeld)
+    );
+    Ok((reader.into_dimensions()?, streamable))
+}
+
+/**
+ * decodes an image file straight into a `width`x`height` image, averaging every block of pixels
+ */
+pub fn open_downsampled(path: &Path, width: u32, height: u32) -> ImageResult<image::RgbImage> {
+    let format = image::io::Reader::open(path)?.with_guessed_format()?.format();
+    let file = BufReader::new(File::open(path)?);
+    match format {
+        Some(ImageFormat::Png) => downsample(png::PngDecoder::new(file)?, width, height),
+        Some(ImageFormat::Bmp) => downsample(bmp::BmpDecoder::new(file)?, width, height),
+        Some(ImageFormat::Pnm) => downsample(pnm::PnmDecoder::new(file)?, width, height),
+        Some(ImageFormat::Farbfeld) => downsample(farbfeld::FarbfeldDecoder::new(file)?, width, height),
+        //everything else has to be decoded whole
+        _ => Ok(image::open(path)?.resize_exact(width, height, image::imageops::Triangle).into_rgb8()),
+    }
+}
+
+/**
+ * reads a decoder one row at a time into a `width`x`height` image, every output pixel is the average
+ * of the block of input pixels that lands on it
+ *
+ * the output can't be bigger than the input in either direction
+ */
+pub fn downsample<'a, D: ImageDecoder<'a>>(decoder: D, width: u32, height: u32) -> ImageResult<image::RgbImage> {
+    let (src_width, src_height) = decoder.dimensions();
+    let color_type = decoder.color_type();
+    if width == 0 || height == 0 || width > src_width || height > src_height {
+        return Err(ImageError::Parameter(image::error::ParameterError::from_kind(
+            image::error::ParameterErrorKind::DimensionMismatch,
+        )));
+    }
+
+    let mut reader = decoder.into_reader()?;
+    let mut row = vec![0; src_width as usize * color_type.bytes_per_pixel() as usize];
+    //running totals of red, green, blue and the number of pixels, for every output pixel
+    let mut totals = vec![[0u64; 4]; (width * height) as usize];
+
+    //which output column every input column lands in, the same for every row
+    let columns: Vec<usize> = (0..src_width as u64).map(|x| (x * width as u64 / src_width as u64) as usize).collect();
+
+    for y in 0..src_height as u64 {
+        reader.read_exact(&mut row)?;
+        let out_row = (y * height as u64 / src_height as u64) as usize * width as usize;
+        for (x, column) in columns.iter().enumerate() {
+            let [r, g, b] = rgb_at(&row, x, color_type)?;
+            let total = &mut totals[out_row + column];
+            total[0] += r as u64;
+            total[1] += g as u64;
+            total[2] += b as u64;
+            total[3] += 1;
+        }
+    }
+
+    Ok(image::RgbImage::from_fn(width, height, |x, y| {
+        let [r, g, b, count] = totals[(y * width + x) as usize];
+        image::Rgb([(r / count) as u8, (g / count) as u8, (b / count) as u8])
+    }))
+}
+
+/**
+ * the red, green and blue of the pixel at x in a row of raw pixels, 16 bit channels are cut down to 8 bits
+ * and alpha is dropped
+ */
+fn rgb_at(row: &[u8], x: usize, color_type: ColorType) -> ImageResult<[u8; 3]> {
+    let pixel = &row[x * color_type.bytes_per_pixel() as usize..];
+    //16 bit channels are native endian
+    let wide = |channel: usize| (u16::from_ne_bytes([pixel[channel * 2], pixel[channel * 2 + 1]]) >> 8) as u8;
+    match color_type {
+        ColorType::L8 | ColorType::La8 => Ok([pixel[0], pixel[0], pixel[0]]),
+        ColorType::Rgb8 | ColorType::Rgba8 => Ok([pixel[0], pixel[1], pixel[2]]),
+        ColorType::Bgr8 | ColorType::Bgra8 => Ok([pixel[2], pixel[1], pixel[0]]),
+        ColorType::L16 | ColorType::La16 => Ok([wide(0), wide(0), wide(0)]),
+        ColorType::Rgb16 | ColorType::Rgba16 => Ok([wide(0), wide(1), wide(2)]),
+        _ => Err(ImageError::Unsupported(image::error::UnsupportedError::from_format_and_kind(
+            image::error::ImageFormatHint::Unknown,
+            image::error::UnsupportedErrorKind::Color(color_type.into()),
+        ))),
+    }
+}
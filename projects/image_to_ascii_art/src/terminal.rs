@@ -0,0 +1,48 @@
+//! sizing art to fit the terminal, and correcting for terminal cells being taller than they are wide
+
+use std::env;
+
+/// how many times taller than wide a terminal cell is, most monospace fonts are close to 2
+pub const DEFAULT_CELL_ASPECT: f32 = 2.0;
+
+/// size used when stdout isn't a terminal and no size was asked for, the classic 80x24
+pub const FALLBACK_SIZE: (u32, u32) = (80, 24);
+
+/**
+ * the size of the terminal stdout is attached to, in (columns, rows)
+ *
+ * falls back to the COLUMNS and LINES environment variables, and None if neither is available
+ */
+pub fn terminal_size() -> Option<(u32, u32)> {
+    if let Some((terminal_size::Width(cols), terminal_size::Height(rows))) = terminal_size::terminal_size() {
+        return Some((cols as u32, rows as u32));
+    }
+    let from_env = |name: &str| env::var(name).ok().and_then(|v| v.parse::<u32>().ok()).filter(|v| *v > 0);
+    match (from_env("COLUMNS"), from_env("LINES")) {
+        (Some(cols), Some(rows)) => Some((cols, rows)),
+        _ => None,
+    }
+}
+
+/**
+ * finds the biggest grid of characters, in (columns, rows), that shows an image with its shape intact
+ * without going over `max_cols` columns or `max_rows` rows
+ *
+ * `cell_aspect` is how many times taller than wide a character is
+ */
+pub fn fit(img_width: u32, img_height: u32, max_cols: u32, max_rows: Option<u32>, cell_aspect: f32) -> (u32, u32) {
+    if img_width == 0 || img_height == 0 {
+        return (0, 0);
+    }
+    let shape = img_height as f32 / img_width as f32 / cell_aspect;
+
+    let mut cols = max_cols.max(1);
+    let mut rows = ((cols as f32 * shape).round() as u32).max(1);
+    if let Some(max_rows) = max_rows {
+        if rows > max_rows.max(1) {
+            rows = max_rows.max(1);
+            cols = ((rows as f32 / shape).round() as u32).clamp(1, cols);
+        }
+    }
+    (cols, rows)
+}
@@ -0,0 +1,100 @@
+//! golden snapshot tests, small synthetic images rendered in every mode and palette and compared to the
+//! expected text checked in under tests/snapshots
+//!
+//! when a change to rendering is on purpose, rewrite the expected text with:
+//! `UPDATE_SNAPSHOTS=1 cargo test --test snapshots`
+//! and look over the diff of tests/snapshots before committing it
+
+use image::{DynamicImage, Rgb, RgbImage};
+use image_to_ascii_art::{AsciiRenderer, Dither, Mode, Palette};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// size of the synthetic images, in pixels
+const SIZE: u32 = 32;
+
+/// width of the rendered art, in characters
+const WIDTH: u32 = 16;
+
+/// the synthetic images, by name
+fn fixtures() -> Vec<(&'static str, DynamicImage)> {
+    let gray = |v: u32| Rgb([v as u8, v as u8, v as u8]);
+    vec![
+        ("gradient", RgbImage::from_fn(SIZE, SIZE, |x, _| gray(x * 255 / (SIZE - 1)))),
+        ("diagonal_gradient", RgbImage::from_fn(SIZE, SIZE, |x, y| gray((x + y) * 255 / (2 * SIZE - 2)))),
+        ("checkerboard", RgbImage::from_fn(SIZE, SIZE, |x, y| gray(if (x / 8 + y / 8) % 2 == 0 {0} else {255}))),
+        ("solid", RgbImage::from_pixel(SIZE, SIZE, Rgb([40, 120, 200]))),
+        ("circle", RgbImage::from_fn(SIZE, SIZE, |x, y| {
+            let (dx, dy) = (x as i32 - 16, y as i32 - 16);
+            gray(if dx * dx + dy * dy < 100 {20} else {230})
+        })),
+    ]
+    .into_iter()
+    .map(|(name, img)| (name, DynamicImage::ImageRgb8(img)))
+    .collect()
+}
+
+/// every way the fixtures are rendered, by name
+fn renderers() -> Vec<(&'static str, AsciiRenderer)> {
+    let base = || AsciiRenderer::new().width(WIDTH);
+    vec![
+        ("blocks", base().mode(Mode::Blocks).palette(Palette::blocks())),
+        ("blocks_ascii", base().mode(Mode::Blocks).palette(Palette::ascii())),
+        ("blocks_floyd_steinberg", base().mode(Mode::Blocks).palette(Palette::ascii()).dither(Dither::FloydSteinberg)),
+        ("braille", base().mode(Mode::Braille)),
+        ("braille_bayer", base().mode(Mode::Braille).dither(Dither::Bayer(4))),
+        ("edges", base().mode(Mode::Edges).palette(Palette::blocks())),
+        ("edges_ascii", base().mode(Mode::Edges).palette(Palette::ascii())),
+    ]
+}
+
+fn snapshot_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("snapshots").join(format!("{}.txt", name))
+}
+
+#[test]
+fn renders_match_snapshots() {
+    let update = env::var_os("UPDATE_SNAPSHOTS").is_some();
+    let mut mismatches = Vec::new();
+
+    for (image_name, img) in fixtures() {
+        for (renderer_name, renderer) in renderers() {
+            let name = format!("{}__{}", image_name, renderer_name);
+            let path = snapshot_path(&name);
+            let actual = renderer.render(&img).into_string();
+
+            if update {
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(&path, &actual).unwrap();
+                continue;
+            }
+            match fs::read_to_string(&path) {
+                Ok(expected) if expected == actual => {}
+                Ok(expected) => mismatches.push(format!("{}:\n--- expected\n{}--- actual\n{}", name, expected, actual)),
+                Err(_) => mismatches.push(format!("{}: no snapshot at {}", name, path.display())),
+            }
+        }
+    }
+
+    assert!(
+        mismatches.is_empty(),
+        "{} renders didn't match their snapshots, run `UPDATE_SNAPSHOTS=1 cargo test --test snapshots` if the change is on purpose\n\n{}",
+        mismatches.len(),
+        mismatches.join("\n")
+    );
+}
+
+#[test]
+fn the_shipped_image_matches_its_snapshot() {
+    let img = image::open(concat!(env!("CARGO_MANIFEST_DIR"), "/simple_4_color_gray.png")).unwrap();
+    let actual = AsciiRenderer::new().width(40).render(&img).into_string();
+    let path = snapshot_path("simple_4_color_gray");
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).expect("missing snapshot, run `UPDATE_SNAPSHOTS=1 cargo test --test snapshots`");
+    assert_eq!(expected, actual);
+}
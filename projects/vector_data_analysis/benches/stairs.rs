@@ -0,0 +1,52 @@
+//! regenerates the table of stairs against memory and time, run with `cargo bench --bench stairs`
+//!
+//! the values are the same on every run for a given seed, so runs on different machines or commits can be compared.
+//! STAIRS_BENCH_LEN sets how many values are sorted (10 million by default) and STAIRS_BENCH_SEED the seed
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::env;
+use std::time::Instant;
+use vector_data_analysis::{memory, stair_sort};
+
+#[global_allocator]
+static GLOBAL: memory::TrackingAllocator = memory::TrackingAllocator;
+
+fn env_or(name: &str, default: u64) -> u64 {
+    env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+fn main() {
+    let len = env_or("STAIRS_BENCH_LEN", 10_000_000) as usize;
+    let seed = env_or("STAIRS_BENCH_SEED", 42);
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let values: Vec<usize> = (0..len).map(|_| rng.gen_range(0..=1_000_000)).collect();
+
+    let range = values.iter().max().unwrap_or(&0) - values.iter().min().unwrap_or(&0);
+    let auto = stair_sort::auto_stairs(len, range as u64, stair_sort::available_memory().unwrap_or(1 << 30));
+    println!("sorting {} values from 0 to 1,000,000, seed {}, auto picks {} stairs\n", len, seed, auto);
+    println!("stairs           | peak extra memory (MB) | time to sort (s)");
+    println!("---------------- | ---------------------- | ----------------");
+    //the last row sorts on every core
+    let runs = [stair_sort::AUTO, 1, 5, 10, 20, 50, 100].iter().map(|stairs| (*stairs, false)).chain([(stair_sort::AUTO, true)]);
+    for (stairs, parallel) in runs {
+        let mut v = values.clone();
+        let start = Instant::now();
+        let ((), peak) = memory::peak_during(|| {
+            if parallel {
+                stair_sort::par_sort(&mut v, stairs);
+            } else {
+                stair_sort::sort(&mut v, stairs);
+            }
+        });
+        let elapsed = start.elapsed().as_secs_f64();
+        assert!(v.windows(2).all(|w| w[0] <= w[1]), "{} stairs didn't sort the values", stairs);
+
+        let label = match (stairs, parallel) {
+            (_, true) => format!("auto, {} threads", stair_sort::thread_count()),
+            (stair_sort::AUTO, false) => format!("auto ({})", auto),
+            (stairs, false) => stairs.to_string(),
+        };
+        println!("{:<16} | {:>22.1} | {:>16.6}", label, peak as f64 / 1e6, elapsed);
+    }
+}
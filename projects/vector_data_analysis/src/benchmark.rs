@@ -0,0 +1,314 @@
+//! the `bench` subcommand, timing stair sort against other sorts on generated data sets
+//!
+//! the data sets come from a seeded ChaCha generator, so the same seed gives the same values on every machine
+
+use crate::{memory, stair_sort};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+use std::time::Instant;
+
+/// the largest value in the uniform data set, the same as the random vectors of the analysis
+pub const UNIFORM_MAX: u64 = 1_000_000;
+/// how many different values the few unique data set has
+pub const FEW_UNIQUE_VALUES: u64 = 16;
+/// how many ranks the zipf data set picks from, and the exponent of its distribution
+pub const ZIPF_RANKS: usize = 100_000;
+pub const ZIPF_EXPONENT: f64 = 1.1;
+
+/// the shape of a generated data set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dataset {
+    /// every value from 0 to `UNIFORM_MAX` is as likely
+    Uniform,
+    /// a few values are very common and most are rare
+    Zipf,
+    /// already in ascending order
+    Sorted,
+    /// in descending order
+    ReverseSorted,
+    /// only `FEW_UNIQUE_VALUES` different values
+    FewUnique,
+}
+impl Dataset {
+    pub const ALL: [Dataset; 5] = [Dataset::Uniform, Dataset::Zipf, Dataset::Sorted, Dataset::ReverseSorted, Dataset::FewUnique];
+
+    /**
+     * generates `len` values, the same ones for the same seed
+     */
+    pub fn generate(&self, len: usize, seed: u64) -> Vec<u64> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        match self {
+            Dataset::Uniform => (0..len).map(|_| rng.gen_range(0..=UNIFORM_MAX)).collect(),
+            Dataset::Zipf => {
+                //the chance of rank k is proportional to 1/k^s, picked by searching the running total of the chances
+                let mut cumulative = Vec::with_capacity(ZIPF_RANKS);
+                let mut total = 0.0;
+                for rank in 1..=ZIPF_RANKS {
+                    total += 1.0 / (rank as f64).powf(ZIPF_EXPONENT);
+                    cumulative.push(total);
+                }
+                (0..len)
+                    .map(|_| {
+                        let target = rng.gen::<f64>() * total;
+                        cumulative.partition_point(|c| *c < target).min(ZIPF_RANKS - 1) as u64 + 1
+                    })
+                    .collect()
+            }
+            Dataset::Sorted => (0..len as u64).collect(),
+            Dataset::ReverseSorted => (0..len as u64).rev().collect(),
+            Dataset::FewUnique => (0..len).map(|_| rng.gen_range(0..FEW_UNIQUE_VALUES) * 1000).collect(),
+        }
+    }
+}
+impl FromStr for Dataset {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "uniform" => Ok(Dataset::Uniform),
+            "zipf" => Ok(Dataset::Zipf),
+            "sorted" => Ok(Dataset::Sorted),
+            "reverse" | "reverse-sorted" => Ok(Dataset::ReverseSorted),
+            "few-unique" => Ok(Dataset::FewUnique),
+            _ => Err("unknown data set, expected one of: uniform, zipf, sorted, reverse-sorted, few-unique"),
+        }
+    }
+}
+impl fmt::Display for Dataset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Dataset::Uniform => "uniform",
+            Dataset::Zipf => "zipf",
+            Dataset::Sorted => "sorted",
+            Dataset::ReverseSorted => "reverse-sorted",
+            Dataset::FewUnique => "few-unique",
+        })
+    }
+}
+
+/// a sort to time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    StairSort,
+    ParallelStairSort,
+    /// one count array over the whole range
+    CountingSort,
+    /// LSD radix sort over the whole slice
+    RadixSort,
+    SortUnstable,
+    Sort,
+}
+impl Algorithm {
+    pub const ALL: [Algorithm; 6] = [
+        Algorithm::StairSort,
+        Algorithm::ParallelStairSort,
+        Algorithm::CountingSort,
+        Algorithm::RadixSort,
+        Algorithm::SortUnstable,
+        Algorithm::Sort,
+    ];
+
+    pub fn sort(&self, v: &mut [u64]) {
+        match self {
+            Algorithm::StairSort => stair_sort::sort(v, stair_sort::AUTO),
+            Algorithm::ParallelStairSort => stair_sort::par_sort(v, stair_sort::AUTO),
+            Algorithm::CountingSort => stair_sort::sort_with_strategy(v, stair_sort::Strategy::Count),
+            Algorithm::RadixSort => stair_sort::sort_with_strategy(v, stair_sort::Strategy::Radix),
+            Algorithm::SortUnstable => v.sort_unstable(),
+            Algorithm::Sort => v.sort(),
+        }
+    }
+}
+impl FromStr for Algorithm {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "stair" | "stair-sort" => Ok(Algorithm::StairSort),
+            "par-stair" | "parallel-stair-sort" => Ok(Algorithm::ParallelStairSort),
+            "counting" | "counting-sort" => Ok(Algorithm::CountingSort),
+            "radix" | "radix-sort" => Ok(Algorithm::RadixSort),
+            "sort-unstable" => Ok(Algorithm::SortUnstable),
+            "sort" => Ok(Algorithm::Sort),
+            _ => Err("unknown algorithm, expected one of: stair-sort, parallel-stair-sort, counting-sort, radix-sort, sort-unstable, sort"),
+        }
+    }
+}
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Algorithm::StairSort => "stair-sort",
+            Algorithm::ParallelStairSort => "parallel-stair-sort",
+            Algorithm::CountingSort => "counting-sort",
+            Algorithm::RadixSort => "radix-sort",
+            Algorithm::SortUnstable => "sort-unstable",
+            Algorithm::Sort => "sort",
+        })
+    }
+}
+
+/// how the results are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Markdown,
+}
+impl FromStr for Format {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(Format::Csv),
+            "markdown" | "md" => Ok(Format::Markdown),
+            _ => Err("unknown format, expected one of: csv, markdown"),
+        }
+    }
+}
+
+/// the options of the bench subcommand
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub len: usize,
+    pub seed: u64,
+    pub datasets: Vec<Dataset>,
+    pub algorithms: Vec<Algorithm>,
+    pub format: Format,
+}
+impl Options {
+    /**
+     * parses the args after `bench`
+     *
+     * usage: `vector_data_analysis bench [--len=<n>] [--seed=<n>] [--datasets=<name>,...] [--algorithms=<name>,...]
+     *         [--format=<csv|markdown>]`
+     */
+    pub fn new(args: &[String]) -> Result<Options, &'static str> {
+        let mut options = Options {
+            len: 1_000_000,
+            seed: 42,
+            datasets: Dataset::ALL.to_vec(),
+            algorithms: Algorithm::ALL.to_vec(),
+            format: Format::Markdown,
+        };
+        for arg in args {
+            if let Some(value) = arg.strip_prefix("--len=") {
+                options.len = value.parse().map_err(|_| "len must be a whole number")?;
+            } else if let Some(value) = arg.strip_prefix("--seed=") {
+                options.seed = value.parse().map_err(|_| "seed must be a whole number")?;
+            } else if let Some(value) = arg.strip_prefix("--datasets=") {
+                options.datasets = value.split(',').map(|name| name.parse()).collect::<Result<_, _>>()?;
+            } else if let Some(value) = arg.strip_prefix("--algorithms=") {
+                options.algorithms = value.split(',').map(|name| name.parse()).collect::<Result<_, _>>()?;
+            } else if let Some(value) = arg.strip_prefix("--format=") {
+                options.format = value.parse()?;
+            } else {
+                return Err("unknown bench option");
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// how one algorithm did on one data set
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchResult {
+    pub dataset: Dataset,
+    pub algorithm: Algorithm,
+    pub len: usize,
+    pub seconds: f64,
+    /// the most extra memory the sort had at once, None when memory isn't being tracked
+    pub peak_bytes: Option<usize>,
+    /// whether the output matched the standard library's sort
+    pub correct: bool,
+}
+
+/**
+ * times every algorithm on every data set
+ */
+pub fn run_benchmarks(options: &Options) -> Vec<BenchResult> {
+    let mut results = Vec::new();
+    for dataset in &options.datasets {
+        let values = dataset.generate(options.len, options.seed);
+        let mut expected = values.clone();
+        expected.sort_unstable();
+        for algorithm in &options.algorithms {
+            let mut v = values.clone();
+            let start = Instant::now();
+            let ((), peak) = memory::peak_during(|| algorithm.sort(&mut v));
+            let seconds = start.elapsed().as_secs_f64();
+            results.push(BenchResult {
+                dataset: *dataset,
+                algorithm: *algorithm,
+                len: options.len,
+                seconds,
+                peak_bytes: if memory::is_tracking() {Some(peak)} else {None},
+                correct: v == expected,
+            });
+        }
+    }
+    results
+}
+
+/**
+ * the results as CSV, with a header row
+ */
+pub fn to_csv(results: &[BenchResult]) -> String {
+    let mut csv = String::from("dataset,algorithm,len,seconds,peak_bytes,correct\n");
+    for r in results {
+        let peak = r.peak_bytes.map(|p| p.to_string()).unwrap_or_default();
+        writeln!(csv, "{},{},{},{:.6},{},{}", r.dataset, r.algorithm, r.len, r.seconds, peak, r.correct).unwrap();
+    }
+    csv
+}
+
+/**
+ * the results as a Markdown table
+ */
+pub fn to_markdown(results: &[BenchResult]) -> String {
+    let mut table = String::from("| dataset | algorithm | len | time (s) | peak memory (MB) | correct |\n");
+    table.push_str("| --- | --- | ---: | ---: | ---: | --- |\n");
+    for r in results {
+        let peak = r.peak_bytes.map(|p| format!("{:.1}", p as f64 / 1e6)).unwrap_or_else(|| "-".to_string());
+        writeln!(table, "| {} | {} | {} | {:.6} | {} | {} |", r.dataset, r.algorithm, r.len, r.seconds, peak, if r.correct {"yes"} else {"NO"}).unwrap();
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats;
+
+    #[test]
+    fn benchmark_data_sets_are_reproducible() {
+        for dataset in Dataset::ALL {
+            assert_eq!(dataset.generate(1000, 7), dataset.generate(1000, 7));
+            assert_eq!(dataset.to_string().parse::<Dataset>(), Ok(dataset));
+        }
+        assert_ne!(Dataset::Uniform.generate(1000, 7), Dataset::Uniform.generate(1000, 8));
+        assert_eq!(Dataset::ReverseSorted.generate(3, 0), vec![2, 1, 0]);
+
+        let few = Dataset::FewUnique.generate(10_000, 1);
+        assert!(stats::modes(&few).len() < FEW_UNIQUE_VALUES as usize + 1);
+        assert!(few.iter().all(|v| *v < FEW_UNIQUE_VALUES * 1000));
+        //the most common zipf value is the first rank
+        assert_eq!(stats::modes(&Dataset::Zipf.generate(10_000, 1)), vec![1]);
+    }
+
+    #[test]
+    fn benchmarks_check_every_sort() {
+        let args: Vec<String> = ["--len=2000", "--datasets=zipf,reverse-sorted", "--format=csv"].iter().map(|s| s.to_string()).collect();
+        let options = Options::new(&args).unwrap();
+        let results = run_benchmarks(&options);
+        assert_eq!(results.len(), 2 * Algorithm::ALL.len());
+        assert!(results.iter().all(|result| result.correct));
+
+        let csv = to_csv(&results);
+        assert!(csv.starts_with("dataset,algorithm,len,seconds,peak_bytes,correct\nzipf,stair-sort,2000,"));
+        assert_eq!(csv.lines().count(), results.len() + 1);
+        assert!(to_markdown(&results).contains("| reverse-sorted | sort | 2000 |"));
+
+        assert!(Options::new(&["--datasets=normal".to_string()]).is_err());
+    }
+}
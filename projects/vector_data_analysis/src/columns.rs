@@ -0,0 +1,134 @@
+//! reading named columns of numbers out of CSV files
+//!
+//! a cell that doesn't parse doesn't stop the analysis, it's recorded with its row and left out, as are empty cells
+
+use crate::stats;
+use std::error::Error;
+use std::fmt;
+use std::io::Read;
+
+/// cells that mean there's no value, compared ignoring case
+pub const MISSING: [&str; 4] = ["", "na", "n/a", "null"];
+/// how many parse errors of a column are printed in its report
+pub const MAX_ERRORS_SHOWN: usize = 10;
+
+/// a cell that wasn't a number
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellError {
+    /// the line of the file the row starts on
+    pub line: u64,
+    pub value: String,
+}
+impl fmt::Display for CellError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: \"{}\" isn't a number", self.line, self.value)
+    }
+}
+
+/// the numbers of one column, and what was left out of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub name: String,
+    pub values: Vec<f64>,
+    /// how many rows had no value
+    pub missing: usize,
+    pub errors: Vec<CellError>,
+}
+impl Column {
+    pub fn new(name: &str) -> Column {
+        Column { name: name.to_string(), values: Vec::new(), missing: 0, errors: Vec::new() }
+    }
+
+    /// how many rows the column was read from
+    pub fn rows(&self) -> usize {
+        self.values.len() + self.missing + self.errors.len()
+    }
+
+    /**
+     * adds the values of the same column from another file
+     */
+    pub fn extend(&mut self, other: Column) {
+        self.values.extend(other.values);
+        self.missing += other.missing;
+        self.errors.extend(other.errors);
+    }
+
+    /**
+     * a report of what was read, followed by every statistic of the values, see `stats::Summary`
+     */
+    pub fn report(&self, percentiles: &[f64], buckets: usize) -> String {
+        let mut report = format!("column:   {}\nrows:     {}\nmissing:  {}\nerrors:   {}\n", self.name, self.rows(), self.missing, self.errors.len());
+        for error in self.errors.iter().take(MAX_ERRORS_SHOWN) {
+            report.push_str(&format!("  {}\n", error));
+        }
+        if self.errors.len() > MAX_ERRORS_SHOWN {
+            report.push_str(&format!("  and {} more\n", self.errors.len() - MAX_ERRORS_SHOWN));
+        }
+        match stats::Summary::new(&self.values, percentiles, buckets) {
+            Some(summary) => report.push_str(&summary.to_string()),
+            None => report.push_str("no numbers to analyze\n"),
+        }
+        report
+    }
+}
+
+/**
+ * reads the named columns out of CSV with a header row, in the order they're asked for
+ *
+ * names are trimmed like the header is, so " price" and "price" both find a " price " column
+ *
+ * rows too short to have a column count as missing in it, it's an error for a column not to be in the header, or for
+ * the CSV itself to be malformed
+ */
+pub fn read_columns<R: Read>(reader: R, names: &[String], delimiter: u8) -> Result<Vec<Column>, Box<dyn Error>> {
+    let mut reader = csv::ReaderBuilder::new().delimiter(delimiter).flexible(true).trim(csv::Trim::All).from_reader(reader);
+    let headers = reader.headers()?.clone();
+    let names: Vec<&str> = names.iter().map(|name| name.trim()).collect();
+    let indices = names
+        .iter()
+        .map(|name| headers.iter().position(|header| header == *name).ok_or_else(|| format!("there's no column named \"{}\"", name)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut columns: Vec<Column> = names.iter().map(|name| Column::new(name)).collect();
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map_or(0, |position| position.line());
+        for (column, index) in columns.iter_mut().zip(&indices) {
+            let cell = record.get(*index).unwrap_or("");
+            if MISSING.contains(&cell.to_lowercase().as_str()) {
+                column.missing += 1;
+                continue;
+            }
+            match cell.parse::<f64>() {
+                Ok(value) if value.is_finite() => column.values.push(value),
+                _ => column.errors.push(CellError { line, value: cell.to_string() }),
+            }
+        }
+    }
+    Ok(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_columns_record_missing_values_and_errors() {
+        let csv = "id; price ;name\n1;2.5;a\n2;;b\n3;oops;c\n4;NA;d\n5;-1e1;e\n6\n";
+        let names = vec!["price".to_string(), "id".to_string()];
+        let read = read_columns(csv.as_bytes(), &names, b';').unwrap();
+        let (price, id) = (&read[0], &read[1]);
+        assert_eq!(price.values, vec![2.5, -10.0]);
+        assert_eq!((price.missing, price.rows()), (3, 6));
+        assert_eq!(price.errors, vec![CellError { line: 4, value: "oops".to_string() }]);
+        assert_eq!(id.values, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+        let report = price.report(&[50.0], 2);
+        assert!(report.starts_with("column:   price\nrows:     6\nmissing:  3\nerrors:   1\n  line 4: \"oops\" isn't a number\ncount:    2\n"));
+        assert!(Column::new("empty").report(&[], 1).ends_with("no numbers to analyze\n"));
+        assert!(read_columns(csv.as_bytes(), &["cost".to_string()], b';').is_err());
+        //names are trimmed like the header, as in --columns="price, id"
+        let spaced = read_columns(csv.as_bytes(), &[" price".to_string(), "id ".to_string()], b';').unwrap();
+        assert_eq!(spaced, read);
+    }
+}
@@ -0,0 +1,360 @@
+//! the `sort` subcommand, sorting files of integers bigger than memory
+//!
+//! the input is cut into chunks that fit in the memory limit, every chunk is stair sorted and spilled to a temp
+//! file as a run, then the runs are merged through a heap, a few at a time if there are too many to open at once
+
+use crate::stair_sort::{self, RadixKey, Strategy};
+use crate::streaming;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::convert::TryFrom;
+use std::env;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// the memory limit when none is asked for
+pub const DEFAULT_MEMORY: usize = 256 << 20;
+/// how many runs are merged at once, each one is an open file
+pub const DEFAULT_FAN_IN: usize = 64;
+/// chunks are never smaller than this many values, however low the memory limit
+pub const MIN_CHUNK_LEN: usize = 256;
+/// the most bytes a value of a chunk costs while it's sorted: the value, stair sort's copy of it, and the counts of
+/// up to `Strategy::DENSE_KEYS_PER_VALUE` keys when its bin is count sorted
+const BYTES_PER_VALUE: usize = (2 + Strategy::DENSE_KEYS_PER_VALUE as usize) * std::mem::size_of::<i64>();
+
+/// how the integers are stored in the input and output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// written out in decimal, separated by whitespace or commas, one per line in the output
+    Text,
+    /// 8 byte little endian signed integers one after another
+    Binary,
+}
+impl FromStr for Format {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Format::Text),
+            "binary" => Ok(Format::Binary),
+            _ => Err("unknown format, expected one of: text, binary"),
+        }
+    }
+}
+
+/// the options of the sort subcommand
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    /// the file to sort and where to write it, "-" is stdin or stdout
+    pub input: String,
+    pub output: String,
+    /// roughly how many bytes the sort may use
+    pub memory: usize,
+    pub format: Format,
+    /// where the runs are spilled
+    pub temp_dir: PathBuf,
+    pub fan_in: usize,
+}
+impl Options {
+    /**
+     * parses the args after `sort`
+     *
+     * usage: `vector_data_analysis sort <input> <output> [--memory=<MiB>] [--format=<text|binary>] [--temp-dir=<dir>]
+     *         [--fan-in=<runs>]`
+     */
+    pub fn new(args: &[String]) -> Result<Options, &'static str> {
+        let mut paths = Vec::new();
+        let mut options = Options {
+            input: String::new(),
+            output: String::new(),
+            memory: DEFAULT_MEMORY,
+            format: Format::Text,
+            temp_dir: env::temp_dir(),
+            fan_in: DEFAULT_FAN_IN,
+        };
+        for arg in args {
+            if let Some(value) = arg.strip_prefix("--memory=") {
+                let mib: usize = value.parse().map_err(|_| "memory must be a whole number of MiB")?;
+                options.memory = mib << 20;
+            } else if let Some(value) = arg.strip_prefix("--format=") {
+                options.format = value.parse()?;
+            } else if let Some(value) = arg.strip_prefix("--temp-dir=") {
+                options.temp_dir = PathBuf::from(value);
+            } else if let Some(value) = arg.strip_prefix("--fan-in=") {
+                options.fan_in = value.parse().map_err(|_| "fan in must be a whole number")?;
+                if options.fan_in < 2 {return Err("fan in must be at least 2");}
+            } else if arg.starts_with("--") {
+                return Err("unknown sort option");
+            } else {
+                paths.push(arg.clone());
+            }
+        }
+        match <[String; 2]>::try_from(paths) {
+            Ok([input, output]) => {
+                options.input = input;
+                options.output = output;
+                Ok(options)
+            }
+            Err(_) => Err("sort needs an input and an output file"),
+        }
+    }
+
+    /// how many values are sorted in memory at once
+    pub fn chunk_len(&self) -> usize {
+        (self.memory / BYTES_PER_VALUE).max(MIN_CHUNK_LEN)
+    }
+}
+
+/// what the sort did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortStats {
+    pub values: u64,
+    /// how many sorted chunks were spilled, 0 when the input fit in memory
+    pub runs: usize,
+    /// how many times the runs were merged, counting the final merge into the output
+    pub merge_passes: usize,
+}
+
+/// a sorted run in a temp file, removed when dropped
+struct Run {
+    path: PathBuf,
+}
+impl Run {
+    /// a new, unique, temp file name in dir
+    fn create(dir: &Path) -> io::Result<(Run, BufWriter<File>)> {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let name = format!("vector_data_analysis-{}-{}.run", std::process::id(), NEXT.fetch_add(1, Ordering::Relaxed));
+        let path = dir.join(name);
+        let file = File::create(&path)?;
+        Ok((Run { path }, BufWriter::new(file)))
+    }
+}
+impl Drop for Run {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/**
+ * sorts the input file into the output file, see `sort_stream`
+ *
+ * the sorted values are written to a temp file next to the output, which is only renamed over it once the whole
+ * input has been read, so a file can be sorted onto itself
+ */
+pub fn sort_file(options: &Options) -> Result<SortStats, Box<dyn Error>> {
+    let input: Box<dyn Read> = if options.input == "-" {Box::new(io::stdin())} else {Box::new(File::open(&options.input)?)};
+    if options.output == "-" {
+        return sort_stream(BufReader::new(input), BufWriter::new(io::stdout()), options);
+    }
+    let output = Path::new(&options.output);
+    let dir = match output.parent() {
+        Some(dir) if dir != Path::new("") => dir,
+        _ => Path::new("."),
+    };
+    let (sorted, writer) = Run::create(dir)?;
+    let stats = sort_stream(BufReader::new(input), writer, options)?;
+    fs::rename(&sorted.path, output)?;
+    Ok(stats)
+}
+
+/**
+ * sorts every integer read from the input into the output, holding no more than `options.chunk_len()` values
+ *
+ * the runs are always spilled as binary, whatever the format of the input and output
+ */
+pub fn sort_stream<R: BufRead, W: Write>(input: R, mut output: W, options: &Options) -> Result<SortStats, Box<dyn Error>> {
+    let chunk_len = options.chunk_len();
+    let mut chunk: Vec<i64> = Vec::with_capacity(chunk_len);
+    let mut runs = Vec::new();
+    let mut values = 0;
+    for_each_value(input, options.format, |value| {
+        values += 1;
+        chunk.push(value);
+        if chunk.len() == chunk_len {
+            runs.push(spill(&mut chunk, options)?);
+        }
+        Ok(())
+    })?;
+
+    //everything fit in memory, nothing to merge
+    if runs.is_empty() {
+        sort_chunk(&mut chunk, options.memory);
+        for value in &chunk {
+            write_value(&mut output, *value, options.format)?;
+        }
+        output.flush()?;
+        return Ok(SortStats { values, runs: 0, merge_passes: 0 });
+    }
+    if !chunk.is_empty() {
+        runs.push(spill(&mut chunk, options)?);
+    }
+    //give the memory of the chunk back before merging
+    drop(chunk);
+
+    let spilled = runs.len();
+    let mut merge_passes = 1;
+    let buffer = (options.memory / (options.fan_in + 1)).max(4096);
+    while runs.len() > options.fan_in {
+        let mut merged = Vec::new();
+        for group in runs.chunks(options.fan_in) {
+            let (run, mut writer) = Run::create(&options.temp_dir)?;
+            merge(group, buffer, &mut writer, Format::Binary)?;
+            writer.flush()?;
+            merged.push(run);
+        }
+        runs = merged;
+        merge_passes += 1;
+    }
+    merge(&runs, buffer, &mut output, options.format)?;
+    output.flush()?;
+    Ok(SortStats { values, runs: spilled, merge_passes })
+}
+
+/**
+ * stair sorts a chunk, with the number of stairs picked for the memory limit rather than the memory that's free
+ */
+fn sort_chunk(chunk: &mut [i64], memory: usize) {
+    let min = chunk.iter().map(RadixKey::radix_key).min().unwrap_or(0);
+    let max = chunk.iter().map(RadixKey::radix_key).max().unwrap_or(0);
+    let stairs = stair_sort::auto_stairs(chunk.len(), max - min, memory);
+    stair_sort::sort_by_key(chunk, stairs, RadixKey::radix_key);
+}
+
+/**
+ * sorts a chunk and writes it to a new run in the temp dir, leaving the chunk empty
+ */
+fn spill(chunk: &mut Vec<i64>, options: &Options) -> Result<Run, Box<dyn Error>> {
+    sort_chunk(chunk, options.memory);
+    let (run, mut writer) = Run::create(&options.temp_dir)?;
+    for value in chunk.iter() {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    writer.flush()?;
+    chunk.clear();
+    Ok(run)
+}
+
+/**
+ * k-way merges some runs into a writer, with a heap of the smallest value left in every run
+ */
+fn merge<W: Write>(runs: &[Run], buffer: usize, output: &mut W, format: Format) -> Result<(), Box<dyn Error>> {
+    let mut readers = Vec::with_capacity(runs.len());
+    for run in runs {
+        readers.push(BufReader::with_capacity(buffer, File::open(&run.path)?));
+    }
+    let mut heap = BinaryHeap::with_capacity(readers.len());
+    for (i, reader) in readers.iter_mut().enumerate() {
+        if let Some(value) = read_binary(reader)? {
+            heap.push(Reverse((value, i)));
+        }
+    }
+    while let Some(Reverse((value, i))) = heap.pop() {
+        write_value(output, value, format)?;
+        if let Some(next) = read_binary(&mut readers[i])? {
+            heap.push(Reverse((next, i)));
+        }
+    }
+    Ok(())
+}
+
+/**
+ * calls f with every integer in the input
+ */
+fn for_each_value<R: BufRead, F: FnMut(i64) -> Result<(), Box<dyn Error>>>(mut input: R, format: Format, mut f: F) -> Result<(), Box<dyn Error>> {
+    match format {
+        Format::Text => streaming::for_each_word(input, |word| {
+            let word = String::from_utf8_lossy(word);
+            f(word.parse().map_err(|_| format!("\"{}\" isn't an integer", word))?)
+        }),
+        Format::Binary => {
+            while let Some(value) = read_binary(&mut input)? {
+                f(value)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/**
+ * the next 8 byte integer, None at the end of the input
+ */
+fn read_binary<R: Read>(reader: &mut R) -> Result<Option<i64>, Box<dyn Error>> {
+    let mut bytes = [0; 8];
+    let mut read = 0;
+    while read < bytes.len() {
+        match reader.read(&mut bytes[read..]) {
+            Ok(0) if read == 0 => return Ok(None),
+            Ok(0) => return Err("the binary input ends part way through an integer".into()),
+            Ok(n) => read += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(Some(i64::from_le_bytes(bytes)))
+}
+
+fn write_value<W: Write>(output: &mut W, value: i64, format: Format) -> io::Result<()> {
+    match format {
+        Format::Text => writeln!(output, "{}", value),
+        Format::Binary => output.write_all(&value.to_le_bytes()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use std::convert::TryInto;
+
+    #[test]
+    fn external_sort_spills_and_merges_runs() {
+        let mut rng = crate::test_rng(31);
+        let v: Vec<i64> = (0..10_000).map(|_| rng.gen::<i64>() >> rng.gen_range(0..60)).collect();
+        let mut expected = v.clone();
+        expected.sort();
+
+        let args: Vec<String> = ["in.txt", "-", "--format=binary", "--fan-in=3"].iter().map(|s| s.to_string()).collect();
+        let mut options = Options::new(&args).unwrap();
+        assert_eq!((options.input.as_str(), options.output.as_str(), options.fan_in), ("in.txt", "-", 3));
+        //the smallest chunks there can be, so there are 40 runs merged in 4 passes
+        options.memory = 0;
+        let binary: Vec<u8> = v.iter().flat_map(|x| x.to_le_bytes()).collect();
+        let mut out = Vec::new();
+        let stats = sort_stream(binary.as_slice(), &mut out, &options).unwrap();
+        assert_eq!(stats, SortStats { values: 10_000, runs: 40, merge_passes: 4 });
+        let sorted: Vec<i64> = out.chunks(8).map(|bytes| i64::from_le_bytes(bytes.try_into().unwrap())).collect();
+        assert_eq!(sorted, expected);
+        assert!(sort_stream(&binary[..9], Vec::new(), &options).is_err());
+
+        //text that fits in memory isn't spilled
+        options.format = Format::Text;
+        options.memory = DEFAULT_MEMORY;
+        let text: Vec<String> = v.iter().map(|x| x.to_string()).collect();
+        let mut out = Vec::new();
+        let stats = sort_stream(text.join(", ").as_bytes(), &mut out, &options).unwrap();
+        assert_eq!((stats.runs, stats.merge_passes), (0, 0));
+        assert_eq!(crate::read_numbers(&String::from_utf8(out).unwrap()).unwrap(), expected.iter().map(|x| *x as f64).collect::<Vec<_>>());
+        assert!(sort_stream("1 2.5".as_bytes(), Vec::new(), &options).is_err());
+        assert!(Options::new(&["in.txt".to_string()]).is_err());
+    }
+
+    #[test]
+    fn sorts_a_file_onto_itself() {
+        let path = env::temp_dir().join(format!("vector_data_analysis-{}-onto-itself.txt", std::process::id()));
+        let values: Vec<String> = (0..1000).rev().map(|x| x.to_string()).collect();
+        fs::write(&path, values.join("\n")).unwrap();
+        let file = path.to_str().unwrap().to_string();
+        let mut options = Options::new(&[file.clone(), file]).unwrap();
+        //small enough chunks that runs are spilled while the input is still being read
+        options.memory = 0;
+        let stats = sort_file(&options).unwrap();
+        let sorted = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!((stats.values, stats.runs), (1000, 4));
+        assert_eq!(crate::read_numbers(&sorted).unwrap(), (0..1000).map(f64::from).collect::<Vec<_>>());
+    }
+}
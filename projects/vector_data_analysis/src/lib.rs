@@ -0,0 +1,350 @@
+pub mod benchmark;
+pub mod columns;
+pub mod external_sort;
+pub mod memory;
+pub mod plot;
+pub mod stair_sort;
+pub mod stats;
+pub mod streaming;
+
+use rand::Rng;
+use std::error::Error;
+use std::fs;
+use std::io::{self, BufReader, Read};
+use std::time::SystemTime;
+
+/// percentiles printed when none are asked for
+pub const DEFAULT_PERCENTILES: [f64; 4] = [25.0, 75.0, 90.0, 99.0];
+/// histogram buckets printed when no number is asked for
+pub const DEFAULT_BUCKETS: usize = 10;
+
+/// the command line options
+pub struct Config {
+    /// files to read numbers from, stdin is read when there are none or the file is "-"
+    pub files: Vec<String>,
+    /// analyze this many random numbers instead of reading any
+    pub random: Option<usize>,
+    pub percentiles: Vec<f64>,
+    pub buckets: usize,
+    /// how many stairs to sort the random numbers with, 0 picks automatically
+    pub stairs: usize,
+    /// sort on every core
+    pub parallel: bool,
+    /// analyze these columns of CSV files instead of every number in the files
+    pub columns: Vec<String>,
+    /// what separates the cells of the CSV files
+    pub delimiter: u8,
+    /// print the numbers sorted after the statistics
+    pub sorted: bool,
+    /// draw a histogram, box plot and sparkline after the statistics, with these characters. when streaming, the
+    /// histogram and box plot are drawn from a random sample of the numbers
+    pub plot: Option<plot::Charset>,
+    /// how many characters wide the plots are
+    pub width: usize,
+    /// read the input in one pass without holding it, estimating the quantiles and most common values
+    pub streaming: bool,
+    /// run the bench subcommand with these options instead of analyzing anything
+    pub bench: Option<benchmark::Options>,
+    /// run the sort subcommand with these options instead of analyzing anything
+    pub sort: Option<external_sort::Options>,
+}
+impl Config {
+    /**
+     * parses the passed args (taken from command line)
+     *
+     * usage: `vector_data_analysis [file ...] [--random=<count>] [--percentiles=<p>,<p>,...] [--buckets=<n>] [--stairs=<n>] [--parallel] [--streaming]
+     *        [--columns=<name>,<name>,...] [--delimiter=<char|tab>] [--sorted] [--plot[=ascii]] [--width=<n>]`
+     *
+     * or `vector_data_analysis bench [options]` to compare sorting algorithms, see `benchmark::Options::new`
+     *
+     * or `vector_data_analysis sort <input> <output> [options]` to sort a file bigger than memory, see `external_sort::Options::new`
+     */
+    pub fn new(args: &[String]) -> Result<Config, &str> {
+        let mut files = Vec::new();
+        let mut random = None;
+        let mut percentiles = DEFAULT_PERCENTILES.to_vec();
+        let mut buckets = DEFAULT_BUCKETS;
+        let mut stairs = stair_sort::AUTO;
+        let mut parallel = false;
+        let mut streaming = false;
+        let mut columns = Vec::new();
+        let mut delimiter = b',';
+        let mut sorted = false;
+        let mut plot = None;
+        let mut width = plot::DEFAULT_WIDTH;
+        let mut bench = None;
+        let mut sort = None;
+        //the first arg is the program name
+        let mut args = args.iter().skip(1);
+        if args.clone().next().map(|arg| arg.as_str()) == Some("bench") {
+            bench = Some(benchmark::Options::new(&args.by_ref().skip(1).cloned().collect::<Vec<_>>())?);
+        } else if args.clone().next().map(|arg| arg.as_str()) == Some("sort") {
+            sort = Some(external_sort::Options::new(&args.by_ref().skip(1).cloned().collect::<Vec<_>>())?);
+        }
+        for arg in args {
+            if let Some(value) = arg.strip_prefix("--random=") {
+                random = Some(value.parse().map_err(|_| "random must be a whole number of values")?);
+            } else if let Some(value) = arg.strip_prefix("--percentiles=") {
+                percentiles = value.split(',').map(|p| p.parse()).collect::<Result<_, _>>().map_err(|_| "percentiles must be numbers separated by commas")?;
+                if percentiles.iter().any(|p| !(0.0..=100.0).contains(p)) {
+                    return Err("percentiles must be between 0 and 100");
+                }
+            } else if let Some(value) = arg.strip_prefix("--buckets=") {
+                buckets = value.parse().map_err(|_| "buckets must be a whole number")?;
+                if buckets == 0 {return Err("there must be at least 1 bucket");}
+            } else if let Some(value) = arg.strip_prefix("--stairs=") {
+                stairs = value.parse().map_err(|_| "stairs must be a whole number, 0 picks automatically")?;
+            } else if arg == "--parallel" {
+                parallel = true;
+            } else if arg == "--streaming" {
+                streaming = true;
+            } else if let Some(value) = arg.strip_prefix("--columns=") {
+                //trimmed once here, so the reports are named like the header
+                columns = value.split(',').map(|name| name.trim().to_string()).collect();
+            } else if let Some(value) = arg.strip_prefix("--delimiter=") {
+                delimiter = match value {
+                    "tab" => b'\t',
+                    _ if value.len() == 1 => value.as_bytes()[0],
+                    _ => return Err("the delimiter must be one character or tab"),
+                };
+            } else if arg == "--sorted" {
+                sorted = true;
+            } else if arg == "--plot" {
+                plot = Some(plot::Charset::Unicode);
+            } else if arg == "--plot=ascii" {
+                plot = Some(plot::Charset::Ascii);
+            } else if let Some(value) = arg.strip_prefix("--width=") {
+                width = value.parse().map_err(|_| "width must be a whole number")?;
+                if width == 0 {return Err("width must be at least 1");}
+            } else if arg.starts_with("--") {
+                return Err("unknown option");
+            } else {
+                files.push(arg.clone());
+            }
+        }
+
+        Ok(Config { files, random, percentiles, buckets, stairs, parallel, columns, delimiter, sorted, plot, width, streaming, bench, sort })
+    }
+}
+
+pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
+    if let Some(options) = &config.bench {
+        let results = benchmark::run_benchmarks(options);
+        match options.format {
+            benchmark::Format::Csv => print!("{}", benchmark::to_csv(&results)),
+            benchmark::Format::Markdown => print!("{}", benchmark::to_markdown(&results)),
+        }
+        if results.iter().any(|result| !result.correct) {
+            return Err("a sort gave the wrong result".into());
+        }
+        return Ok(());
+    }
+    if let Some(options) = &config.sort {
+        let stats = external_sort::sort_file(options)?;
+        eprintln!("sorted {} values from {} runs in {} merge passes", stats.values, stats.runs, stats.merge_passes);
+        return Ok(());
+    }
+    if let Some(count) = config.random {
+        return run_random(&config, count);
+    }
+    if config.streaming {
+        return run_streaming(&config);
+    }
+    if !config.columns.is_empty() {
+        return run_columns(&config);
+    }
+
+    let numbers = if config.files.is_empty() {
+        read_numbers(&read_input("-")?)?
+    } else {
+        let mut numbers = Vec::new();
+        for file in &config.files {
+            numbers.extend(read_numbers(&read_input(file)?)?);
+        }
+        numbers
+    };
+
+    let summary = stats::Summary::new(&numbers, &config.percentiles, config.buckets).ok_or("no numbers to analyze")?;
+    print!("{}", summary);
+    if let Some(charset) = config.plot {
+        print!("{}", plot::plots(&numbers, config.buckets, config.width, charset));
+    }
+    if config.sorted {
+        print_sorted(numbers);
+    }
+    Ok(())
+}
+
+/**
+ * analyzes columns of CSV files, the same column of every file together
+ */
+fn run_columns(config: &Config) -> Result<(), Box<dyn Error>> {
+    let mut columns: Vec<columns::Column> = config.columns.iter().map(|name| columns::Column::new(name)).collect();
+    let stdin = ["-".to_string()];
+    for file in if config.files.is_empty() {&stdin[..]} else {&config.files[..]} {
+        let read = if file == "-" {
+            columns::read_columns(io::stdin(), &config.columns, config.delimiter)
+        } else {
+            columns::read_columns(fs::File::open(file)?, &config.columns, config.delimiter)
+        };
+        for (column, read) in columns.iter_mut().zip(read.map_err(|e| format!("{}: {}", file, e))?) {
+            column.extend(read);
+        }
+    }
+    for (i, column) in columns.into_iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        print!("{}", column.report(&config.percentiles, config.buckets));
+        if let (Some(charset), false) = (config.plot, column.values.is_empty()) {
+            print!("{}", plot::plots(&column.values, config.buckets, config.width, charset));
+        }
+        if config.sorted {
+            print_sorted(column.values);
+        }
+    }
+    Ok(())
+}
+
+/**
+ * prints the numbers in order, one per line
+ */
+fn print_sorted(mut numbers: Vec<f64>) {
+    stair_sort::sort(&mut numbers, stair_sort::AUTO);
+    println!("sorted:");
+    for number in numbers {
+        println!("  {}", number);
+    }
+}
+
+/**
+ * the contents of a file, or of stdin when the path is "-"
+ */
+pub fn read_input(path: &str) -> io::Result<String> {
+    if path == "-" {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        Ok(input)
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+/**
+ * analyzes the input a buffer at a time, so files bigger than memory can be read
+ */
+fn run_streaming(config: &Config) -> Result<(), Box<dyn Error>> {
+    let mut summary = streaming::StreamingSummary::new(&config.percentiles, 0);
+    let stdin = ["-".to_string()];
+    for file in if config.files.is_empty() {&stdin[..]} else {&config.files[..]} {
+        if file == "-" {
+            streaming::for_each_number(io::stdin().lock(), |x| summary.push(x))?;
+        } else {
+            streaming::for_each_number(BufReader::new(fs::File::open(file)?), |x| summary.push(x))?;
+        }
+    }
+    if summary.moments.count() == 0 {
+        return Err("no numbers to analyze".into());
+    }
+    print!("{}", summary);
+    if let Some(charset) = config.plot {
+        print!("{}", plot::sample_plots(summary.sample.samples(), summary.moments.count(), config.buckets, config.width, charset));
+    }
+    Ok(())
+}
+
+/**
+ * parses every number in some text, numbers can be separated by whitespace or commas
+ */
+pub fn read_numbers(text: &str) -> Result<Vec<f64>, Box<dyn Error>> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|word| !word.is_empty())
+        .map(|word| match word.parse::<f64>() {
+            Ok(number) if number.is_finite() => Ok(number),
+            _ => Err(format!("\"{}\" isn't a number", word).into()),
+        })
+        .collect()
+}
+
+/**
+ * analyzes a vector filled with random integers, timing how long it takes to generate and sort it
+ */
+fn run_random(config: &Config, count: usize) -> Result<(), Box<dyn Error>> {
+    //generate a vector filled with random integers
+    let sys_time_before_sort = SystemTime::now();
+    let mut rng = rand::thread_rng();
+    let mut v: Vec<usize> = (0..count).map(|_| rng.gen_range(0..=1_000_000)).collect();
+    let difference = SystemTime::now().duration_since(sys_time_before_sort).expect("time may have gone backwards");
+    println!("generated unsorted array in {} seconds!", difference.as_secs_f32());
+
+    //sort the vector
+    let sys_time_before_sort = SystemTime::now();
+    //the number of stairs is picked from the length and range, `cargo bench --bench stairs` times the alternatives
+    if config.parallel {
+        stair_sort::par_sort(&mut v, config.stairs);
+    } else {
+        stair_sort::sort(&mut v, config.stairs);
+    }
+    let difference = SystemTime::now().duration_since(sys_time_before_sort).expect("time may have gone backwards");
+    println!("sorted array in {} seconds", difference.as_secs_f32());
+
+    let summary = stats::Summary::new(&v, &config.percentiles, config.buckets).ok_or("no numbers to analyze")?;
+    print!("{}", summary);
+    if let Some(charset) = config.plot {
+        print!("{}", plot::plots(&v, config.buckets, config.width, charset));
+    }
+    Ok(())
+}
+
+/**
+ * the random number generator tests draw from, seeded so every run sees the same values
+ */
+#[cfg(test)]
+pub(crate) fn test_rng(seed: u64) -> rand_chacha::ChaCha8Rng {
+    rand::SeedableRng::seed_from_u64(seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_numbers_and_options() {
+        assert_eq!(read_numbers("1, 2.5\n-3\t4e2").unwrap(), vec![1.0, 2.5, -3.0, 400.0]);
+        assert!(read_numbers("1 two 3").is_err());
+        assert!(read_numbers("NaN").is_err());
+
+        let args: Vec<String> = ["prog", "a.txt", "--buckets=4", "-", "--percentiles=10,50"].iter().map(|s| s.to_string()).collect();
+        let config = Config::new(&args).unwrap();
+        assert_eq!(config.files, vec!["a.txt", "-"]);
+        assert_eq!((config.buckets, config.percentiles), (4, vec![10.0, 50.0]));
+        let bad: Vec<String> = ["prog", "--percentiles=150"].iter().map(|s| s.to_string()).collect();
+        assert!(Config::new(&bad).is_err());
+    }
+
+    #[test]
+    fn parses_subcommands_and_analysis_options() {
+        let args: Vec<String> = ["prog", "bench", "--len=2000", "--datasets=zipf,reverse-sorted", "--format=csv"].iter().map(|s| s.to_string()).collect();
+        let options = Config::new(&args).unwrap().bench.unwrap();
+        assert_eq!((options.len, options.datasets), (2000, vec![benchmark::Dataset::Zipf, benchmark::Dataset::ReverseSorted]));
+        let bad: Vec<String> = ["prog", "bench", "--datasets=normal"].iter().map(|s| s.to_string()).collect();
+        assert!(Config::new(&bad).is_err());
+
+        let args: Vec<String> = ["prog", "sort", "in.txt", "-", "--format=binary", "--fan-in=3"].iter().map(|s| s.to_string()).collect();
+        let options = Config::new(&args).unwrap().sort.unwrap();
+        assert_eq!((options.input.as_str(), options.output.as_str(), options.fan_in), ("in.txt", "-", 3));
+        assert_eq!(options.format, external_sort::Format::Binary);
+        let missing: Vec<String> = ["prog", "sort", "in.txt"].iter().map(|s| s.to_string()).collect();
+        assert!(Config::new(&missing).is_err());
+
+        let args: Vec<String> = ["prog", "data.tsv", "--columns=a,b c", "--delimiter=tab", "--sorted"].iter().map(|s| s.to_string()).collect();
+        let config = Config::new(&args).unwrap();
+        assert_eq!((config.columns, config.delimiter, config.sorted), (vec!["a".to_string(), "b c".to_string()], b'\t', true));
+        let spaced: Vec<String> = ["prog", "--columns= id, price "].iter().map(|s| s.to_string()).collect();
+        assert_eq!(Config::new(&spaced).unwrap().columns, vec!["id", "price"]);
+
+        let args: Vec<String> = ["prog", "--plot=ascii", "--width=30"].iter().map(|s| s.to_string()).collect();
+        let config = Config::new(&args).unwrap();
+        assert_eq!((config.plot, config.width), (Some(plot::Charset::Ascii), 30));
+    }
+}
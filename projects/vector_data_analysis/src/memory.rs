@@ -0,0 +1,54 @@
+//! tracking how much memory is allocated, for measuring the peak memory of a sort
+//!
+//! only works in programs that install the tracker as their global allocator:
+//! ```ignore
+//! #[global_allocator]
+//! static GLOBAL: vector_data_analysis::memory::TrackingAllocator = vector_data_analysis::memory::TrackingAllocator;
+//! ```
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// the system allocator, counting the bytes allocated and the most there has been at once
+pub struct TrackingAllocator;
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            INSTALLED.store(true, Ordering::Relaxed);
+            let now = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(now, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+/// whether the tracker is the global allocator, if it isn't nothing is counted
+pub fn is_tracking() -> bool {
+    INSTALLED.load(Ordering::Relaxed)
+}
+
+/// bytes allocated right now
+pub fn allocated() -> usize {
+    ALLOCATED.load(Ordering::Relaxed)
+}
+
+/**
+ * measures the most bytes allocated at once while f runs, on top of what was already allocated
+ */
+pub fn peak_during<R, F: FnOnce() -> R>(f: F) -> (R, usize) {
+    let before = allocated();
+    PEAK.store(before, Ordering::Relaxed);
+    let result = f();
+    (result, PEAK.load(Ordering::Relaxed).saturating_sub(before))
+}
@@ -0,0 +1,230 @@
+//! drawing the analyzed numbers in the terminal: a histogram, a box plot and a sparkline
+//!
+//! values are placed on a line of characters with `stats::bucket_of`, the same bucketing the histogram counts with
+
+use crate::stats::{self, Histogram, Number};
+use std::fmt::Write;
+
+/// the plot width when none is asked for, in characters
+pub const DEFAULT_WIDTH: usize = 60;
+/// how far past the quartiles, in interquartile ranges, a value has to be to be an outlier
+pub const OUTLIER_IQRS: f64 = 1.5;
+
+/// the characters plots are drawn with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Charset {
+    /// block elements, for terminals that can show them
+    Unicode,
+    /// plain ASCII, for everything else
+    Ascii,
+}
+impl Charset {
+    /// the partial blocks of a bar, from an eighth to a whole character
+    fn bar_eighths(&self) -> &'static [char] {
+        match self {
+            Charset::Unicode => &['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'],
+            Charset::Ascii => &['#'],
+        }
+    }
+
+    /// the heights of a sparkline, lowest first
+    fn levels(&self) -> &'static [char] {
+        match self {
+            Charset::Unicode => &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'],
+            Charset::Ascii => &['_', '.', '-', '~', '^', '*', '#'],
+        }
+    }
+
+    /// whisker, whisker end, box, median and outlier
+    fn box_parts(&self) -> (char, char, char, char, char) {
+        match self {
+            Charset::Unicode => ('─', '│', '▒', '┃', '•'),
+            Charset::Ascii => ('-', '|', '=', '#', 'o'),
+        }
+    }
+}
+
+/**
+ * a bar for every bucket of a histogram, the longest bar is `width` characters
+ */
+pub fn histogram(histogram: &Histogram, width: usize, charset: Charset) -> String {
+    let buckets = histogram.counts.len();
+    let labels: Vec<String> = (0..buckets)
+        .map(|bucket| {
+            let (start, end) = histogram.bucket_range(bucket);
+            format!("[{}, {}{}", start, end, if bucket + 1 == buckets {"]"} else {")"})
+        })
+        .collect();
+    let label_width = labels.iter().map(|label| label.chars().count()).max().unwrap_or(0);
+    let most = histogram.counts.iter().copied().max().unwrap_or(0).max(1);
+
+    let mut plot = String::new();
+    for (label, count) in labels.iter().zip(&histogram.counts) {
+        writeln!(plot, "  {:<label_width$} {} {}", label, bar(*count as f64 / most as f64 * width as f64, charset), count, label_width = label_width).unwrap();
+    }
+    plot
+}
+
+/**
+ * a bar `length` characters long, ending in a partial block when the charset has them
+ */
+fn bar(length: f64, charset: Charset) -> String {
+    let eighths = charset.bar_eighths();
+    let parts = (length * eighths.len() as f64).round() as usize;
+    let mut bar: String = std::iter::repeat(eighths[eighths.len() - 1]).take(parts / eighths.len()).collect();
+    if parts % eighths.len() != 0 {
+        bar.push(eighths[parts % eighths.len() - 1]);
+    }
+    bar
+}
+
+/// the five number summary of a box plot, with the values past the whiskers
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxPlot {
+    pub min: f64,
+    pub q1: f64,
+    pub median: f64,
+    pub q3: f64,
+    pub max: f64,
+    /// the whiskers reach the furthest values that aren't outliers
+    pub low_whisker: f64,
+    pub high_whisker: f64,
+    /// values more than `OUTLIER_IQRS` interquartile ranges past the quartiles, in ascending order
+    pub outliers: Vec<f64>,
+}
+impl BoxPlot {
+    /**
+     * the box plot of some numbers, None if there are none
+     */
+    pub fn new<T: Number>(v: &[T]) -> Option<BoxPlot> {
+        let sorted: Vec<f64> = stats::sorted(v).iter().map(|x| x.to_f64()).collect();
+        let (q1, median, q3) = (
+            stats::percentile_of_sorted(&sorted, 25.0)?,
+            stats::percentile_of_sorted(&sorted, 50.0)?,
+            stats::percentile_of_sorted(&sorted, 75.0)?,
+        );
+        let reach = (q3 - q1) * OUTLIER_IQRS;
+        let (low_fence, high_fence) = (q1 - reach, q3 + reach);
+        let inside = |x: &&f64| (low_fence..=high_fence).contains(*x);
+        Some(BoxPlot {
+            min: sorted[0],
+            q1,
+            median,
+            q3,
+            max: sorted[sorted.len() - 1],
+            //the quartiles are always inside the fences, so there's a value inside them
+            low_whisker: *sorted.iter().find(inside).expect("a value is inside the fences"),
+            high_whisker: *sorted.iter().rev().find(inside).expect("a value is inside the fences"),
+            outliers: sorted.iter().filter(|x| !inside(x)).copied().collect(),
+        })
+    }
+
+    /**
+     * the plot on one line `width` characters wide from min to max, with the numbers under it
+     */
+    pub fn draw(&self, width: usize, charset: Charset) -> String {
+        let width = width.max(1);
+        let (whisker, end, fill, middle, outlier) = charset.box_parts();
+        let column = |x: f64| stats::bucket_of(x, self.min, self.max, width);
+        let mut line = vec![' '; width];
+        for cell in &mut line[column(self.low_whisker)..=column(self.high_whisker)] {
+            *cell = whisker;
+        }
+        for cell in &mut line[column(self.q1)..=column(self.q3)] {
+            *cell = fill;
+        }
+        line[column(self.low_whisker)] = end;
+        line[column(self.high_whisker)] = end;
+        line[column(self.median)] = middle;
+        for x in &self.outliers {
+            line[column(*x)] = outlier;
+        }
+
+        let mut plot = format!("  {}\n", line.iter().collect::<String>());
+        writeln!(plot, "  min {}  q1 {}  median {}  q3 {}  max {}", self.min, self.q1, self.median, self.q3, self.max).unwrap();
+        if !self.outliers.is_empty() {
+            writeln!(plot, "  {} outliers outside [{}, {}]", self.outliers.len(), self.low_whisker, self.high_whisker).unwrap();
+        }
+        plot
+    }
+}
+
+/**
+ * the values in the order they came, as one line of heights at most `width` characters long
+ *
+ * when there are more values than characters, each character is the average of a run of values
+ */
+pub fn sparkline<T: Number>(v: &[T], width: usize, charset: Charset) -> String {
+    if v.is_empty() {
+        return String::new();
+    }
+    let per_char = v.len().div_ceil(width.max(1));
+    let points: Vec<f64> = v.chunks(per_char).map(|run| stats::mean(run).expect("chunks aren't empty")).collect();
+    let (min, max) = stats::min_max(&points).expect("there are points");
+    let levels = charset.levels();
+    points.iter().map(|x| levels[stats::bucket_of(*x, min, max, levels.len())]).collect()
+}
+
+/**
+ * every plot of some numbers, each under a heading
+ */
+pub fn plots<T: Number>(v: &[T], buckets: usize, width: usize, charset: Charset) -> String {
+    let mut plots = distribution_plots(v, buckets, width, charset);
+    writeln!(plots, "sparkline:\n  {}", sparkline(v, width, charset)).unwrap();
+    plots
+}
+
+/**
+ * the plots of a random sample of the `seen` values read while streaming
+ *
+ * a sample doesn't keep the order the values came in, so there's no sparkline
+ */
+pub fn sample_plots(sample: &[f64], seen: u64, buckets: usize, width: usize, charset: Charset) -> String {
+    let mut plots = format!("plots of a random sample of {} of {} values:\n", sample.len(), seen);
+    plots.push_str(&distribution_plots(sample, buckets, width, charset));
+    plots
+}
+
+/**
+ * the histogram and box plot of some numbers, each under a heading
+ */
+fn distribution_plots<T: Number>(v: &[T], buckets: usize, width: usize, charset: Charset) -> String {
+    let mut plots = String::from("histogram plot:\n");
+    plots.push_str(&histogram(&Histogram::new(v, buckets), width, charset));
+    if let Some(box_plot) = BoxPlot::new(v) {
+        plots.push_str("box plot:\n");
+        plots.push_str(&box_plot.draw(width, charset));
+    }
+    plots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plots_draw_histograms_box_plots_and_sparklines() {
+        let buckets = stats::Histogram::new(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10], 5);
+        let bars = histogram(&buckets, 6, Charset::Unicode);
+        //the fullest bucket gets the whole width, the others 2/3 of it
+        assert_eq!(bars.lines().next(), Some("  [0, 2)  ████ 2"));
+        assert_eq!(bars.lines().last(), Some("  [8, 10] ██████ 3"));
+        assert!(histogram(&stats::Histogram::new(&[1, 2, 3, 4, 5, 6, 7, 8], 2), 3, Charset::Unicode).contains("███ 4"));
+        assert!(histogram(&stats::Histogram::new(&[1, 1, 1, 2], 2), 2, Charset::Unicode).ends_with("] ▋ 1\n"));
+
+        let box_plot = BoxPlot::new(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 100]).unwrap();
+        assert_eq!((box_plot.q1, box_plot.median, box_plot.q3), (3.25, 5.5, 7.75));
+        assert_eq!((box_plot.low_whisker, box_plot.high_whisker, box_plot.outliers.clone()), (1.0, 9.0, vec![100.0]));
+        let drawn = BoxPlot::new(&[0, 2, 4, 6, 8]).unwrap().draw(9, Charset::Ascii);
+        assert_eq!(drawn.lines().next(), Some("  |-==#==-|"));
+        assert!(BoxPlot::new::<u8>(&[]).is_none());
+
+        assert_eq!(sparkline(&[1, 2, 3, 4, 5, 6, 7, 8], 8, Charset::Unicode), "▁▂▃▄▅▆▇█");
+        //runs of values are averaged to fit the width
+        assert_eq!(sparkline(&[0, 0, 5, 5, 1, 1], 3, Charset::Ascii), "_#.");
+        assert!(plots(&[3.5, -1.0], 2, 10, Charset::Ascii).starts_with("histogram plot:\n"));
+        let sampled = sample_plots(&[3.5, -1.0], 1000, 2, 10, Charset::Ascii);
+        assert!(sampled.starts_with("plots of a random sample of 2 of 1000 values:\nhistogram plot:\n"));
+        assert!(!sampled.contains("sparkline"));
+    }
+}
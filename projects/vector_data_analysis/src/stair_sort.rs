@@ -1,194 +1,483 @@
-use std::collections::HashMap;
-use std::time::SystemTime;
-
-struct VectorInfo {
-    base_vector: Vec<usize>,
-    sorted_vector: Vec<usize>,
-    //length: usize,
-    min: usize,
-    max: usize,
-}
-impl VectorInfo {
-    //constructor
-    fn init(v: Vec<usize>) -> VectorInfo {
-        VectorInfo {
-            base_vector: v.clone(),
-            sorted_vector: Vec::new(),
-            //length: v.len(),
-            min: v[0],
-            max: v[0],
+//! stair sort, a bucket sort that splits the values into "stairs" (bins) by their key, then sorts every bin with
+//! a count array, a radix sort or a comparison sort, whichever suits how spread out the bin is
+//!
+//! ```
+//! let mut v = vec![5i32, -3, 12, 0, -3];
+//! vector_data_analysis::stair_sort::sort(&mut v, 4);
+//! assert_eq!(v, vec![-3, -3, 0, 5, 12]);
+//! ```
+
+use std::fs;
+use std::sync::Mutex;
+use std::thread;
+
+/// pass as the number of stairs to have it picked from the input
+pub const AUTO: usize = 0;
+
+/// the most values that need sorting before another stair pays off, from timing 1-100 stairs on 10 million values
+pub const VALUES_PER_STAIR: usize = 1_000_000;
+
+/// the fewest values a stair should hold on average, below this the bins cost more than they save
+pub const MIN_STAIR_LEN: usize = 1024;
+
+/// bytes used to count every key of a stair's range
+const COUNT_BYTES: u128 = 8;
+
+/// memory assumed to be available when it can't be found out
+const FALLBACK_MEMORY: usize = 1 << 30;
+
+/// something that can be sorted by a u64 key, the key must order the same way the values do
+pub trait RadixKey {
+    fn radix_key(&self) -> u64;
+}
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {
+        $(impl RadixKey for $t {
+            fn radix_key(&self) -> u64 {
+                *self as u64
+            }
+        })*
+    };
+}
+macro_rules! impl_signed {
+    ($($t:ty),*) => {
+        $(impl RadixKey for $t {
+            //flipping the sign bit puts the negative numbers below the positive ones
+            fn radix_key(&self) -> u64 {
+                (*self as i64 as u64) ^ (1 << 63)
+            }
+        })*
+    };
+}
+impl_unsigned!(u8, u16, u32, u64, usize, bool, char);
+impl_signed!(i8, i16, i32, i64, isize);
+impl RadixKey for f64 {
+    //the same order as f64::total_cmp, negative numbers have every bit flipped so bigger magnitudes come first
+    fn radix_key(&self) -> u64 {
+        let bits = self.to_bits();
+        if bits >> 63 == 1 {!bits} else {bits ^ (1 << 63)}
+    }
+}
+impl RadixKey for f32 {
+    fn radix_key(&self) -> u64 {
+        let bits = self.to_bits();
+        (if bits >> 31 == 1 {!bits} else {bits ^ (1 << 31)}) as u64
+    }
+}
+impl<T: RadixKey> RadixKey for &T {
+    fn radix_key(&self) -> u64 {
+        (*self).radix_key()
+    }
+}
+
+/// the values split into bins, stored one bin after another
+struct Bins<T> {
+    /// the values, with every bin's values next to each other
+    values: Vec<T>,
+    /// where every bin starts in values, with the end of the last bin at the end
+    starts: Vec<usize>,
+}
+impl<T: Clone> Bins<T> {
+    /**
+     * splits the values, whose keys go from min to max, into `bin_count` bins of keys, every bin covers the same range of keys
+     */
+    fn init<K: Fn(&T) -> u64>(v: &[T], bin_count: usize, (min, max): (u64, u64), key: &K) -> Bins<T> {
+        let bin_of = |value: &T| bin_index(key(value), (min, max), bin_count);
+
+        //count how big every bin is so the values can be copied straight to where their bin goes
+        let mut starts = vec![0; bin_count + 1];
+        for value in v {
+            starts[bin_of(value) + 1] += 1;
         }
+        for i in 1..starts.len() {
+            starts[i] += starts[i - 1];
+        }
+        //the copy starts out as the values in their original order, then every value is moved to its bin
+        let mut next = starts.clone();
+        let mut values = v.to_vec();
+        for value in v {
+            let bin = bin_of(value);
+            values[next[bin]] = value.clone();
+            next[bin] += 1;
+        }
+
+        Bins { values, starts }
     }
-    //calculate, update, and return, the min and max
-    fn min_max(&mut self) -> (usize, usize) {
-        for i in &self.base_vector {
-            if i > &self.max {
-                self.max = *i;
-            } else if i < &self.min {
-                self.min = *i;
+
+    /**
+     * sorts every bin, writing the sorted values into out. equal keys keep their order
+     */
+    fn sort_into<K: Fn(&T) -> u64>(mut self, out: &mut [T], key: &K) {
+        for i in 0..self.starts.len() - 1 {
+            let (start, end) = (self.starts[i], self.starts[i + 1]);
+            if start == end {
+                continue;
             }
+            sort_bin(&mut self.values[start..end], &mut out[start..end], key);
+        }
+    }
+}
+
+/**
+ * which of `bin_count` bins a key goes in, every bin covers the same share of the keys from min to max
+ */
+fn bin_index(key: u64, (min, max): (u64, u64), bin_count: usize) -> usize {
+    ((key - min) as u128 * bin_count as u128 / ((max - min) as u128 + 1)) as usize
+}
+
+/**
+ * sorts the values of one bin into out, picking a strategy from how spread out the bin is. values is used as scratch
+ */
+fn sort_bin<T: Clone, K: Fn(&T) -> u64>(values: &mut [T], out: &mut [T], key: &K) {
+    //the bin's own min, the bin only covers part of the whole range
+    let min = values.iter().map(key).min().unwrap_or(0);
+    let max = values.iter().map(key).max().unwrap_or(0);
+    match Strategy::pick(values.len(), max - min) {
+        Strategy::Count => count_sort(values, out, min, max, key),
+        Strategy::Radix => radix_sort(values, out, min, max, key),
+        Strategy::Compare => {
+            out.clone_from_slice(values);
+            out.sort_by_key(key);
+        }
+    }
+}
+
+/// how a bin gets sorted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// counting every key of the bin's range in an array, for dense bins
+    Count,
+    /// LSD radix sort a byte at a time, for big sparse bins
+    Radix,
+    /// a stable comparison sort, for small sparse bins
+    Compare,
+}
+impl Strategy {
+    /// the most keys per value a bin can span and still be counted
+    pub const DENSE_KEYS_PER_VALUE: u64 = 4;
+    /// the fewest values a sparse bin needs before radix sorting beats comparing
+    pub const RADIX_MIN_LEN: usize = 64;
+
+    /**
+     * picks how to sort a bin of `len` values whose keys span `range` (max - min)
+     */
+    pub fn pick(len: usize, range: u64) -> Strategy {
+        if range < (len as u64).saturating_mul(Self::DENSE_KEYS_PER_VALUE) {
+            Strategy::Count
+        } else if len >= Self::RADIX_MIN_LEN {
+            Strategy::Radix
+        } else {
+            Strategy::Compare
+        }
+    }
+}
+
+/**
+ * counting sort of values into out, with an array counting every key from min to max
+ */
+fn count_sort<T: Clone, K: Fn(&T) -> u64>(values: &[T], out: &mut [T], min: u64, max: u64, key: &K) {
+    //count occurances of every key, then turn the counts into where every key starts
+    let mut counts = vec![0usize; (max - min) as usize + 1];
+    for value in values {
+        counts[(key(value) - min) as usize] += 1;
+    }
+    let mut position = 0;
+    for count in counts.iter_mut() {
+        let next = position + *count;
+        *count = position;
+        position = next;
+    }
+
+    //place every value after the ones before it with the same key
+    for value in values {
+        let position = &mut counts[(key(value) - min) as usize];
+        out[*position] = value.clone();
+        *position += 1;
+    }
+}
+
+/**
+ * LSD radix sort of values into out, one byte of the key (relative to min) at a time. values is used as scratch
+ */
+fn radix_sort<T: Clone, K: Fn(&T) -> u64>(values: &mut [T], out: &mut [T], min: u64, max: u64, key: &K) {
+    //only the bytes that differ between min and max need sorting
+    let bytes = (64 - (max - min).leading_zeros()).div_ceil(8);
+    //whether the values are sorted so far in out, rather than in values
+    let mut in_out = false;
+    for byte in 0..bytes {
+        let shift = byte * 8;
+        let (from, to): (&[T], &mut [T]) = if in_out {(&*out, &mut *values)} else {(&*values, &mut *out)};
+        let digit = |value: &T| (((key(value) - min) >> shift) & 0xff) as usize;
+
+        let mut counts = [0usize; 256];
+        for value in from.iter() {
+            counts[digit(value)] += 1;
         }
-        (self.min, self.max)
-    }
-    //generate scaled array
-    // fn gen_scaled(&mut self) {
-    //     for i in 0..self.length {
-    //         self.scaled_vector[i] -= self.min;
-    //     }
-    // }
-}
-
-struct Bins {
-    _bin_count: usize,
-    _bin_maxes: Vec<usize>,
-    bins: Vec<Vec<usize>>,
-}
-impl Bins {
-    //constructor
-    fn init(bin_count: usize, v: &mut VectorInfo) -> Bins {
-        let mut maxes: Vec<usize> = Vec::new();
-        for i in 0..=(bin_count-1) {
-            maxes.push( (v.max * i) / bin_count);
+        //every value having the same digit leaves the order as it is
+        if counts.contains(&from.len()) {
+            continue;
         }
-        Bins {
-            _bin_count: bin_count,
-            _bin_maxes: maxes.clone(),
-            bins: {
-                let mut stair_bins: Vec<Vec<usize>> = Vec::new();
-                for _i in 0..bin_count {
-                    stair_bins.push(Vec::new());
-                }
-                for i in v.base_vector.iter()/*.rev()*/ {
-                    let mut best_bin: usize = 0;
-                    for e in 0..maxes.len() {
-                        if *i > maxes[e] {
-                            best_bin = e;
-                        }
+        let mut position = 0;
+        for count in counts.iter_mut() {
+            let next = position + *count;
+            *count = position;
+            position = next;
+        }
+        for value in from.iter() {
+            let position = &mut counts[digit(value)];
+            to[*position] = value.clone();
+            *position += 1;
+        }
+        in_out = !in_out;
+    }
+    if !in_out {
+        out.clone_from_slice(values);
+    }
+}
+
+/**
+ * the amount of memory that's free to use, in bytes, from /proc/meminfo on Linux
+ */
+pub fn available_memory() -> Option<usize> {
+    let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo.lines().find(|line| line.starts_with("MemAvailable:"))?;
+    let kilobytes: usize = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kilobytes * 1024)
+}
+
+/**
+ * picks how many stairs to sort `len` values with, when their keys cover `key_range` and `memory` bytes are free
+ *
+ * one stair per million values is the fastest, but every stair counts its whole share of the range, so
+ * wide ranges get more stairs to keep the counts within a quarter of the memory. stairs never get so many
+ * that they'd hold fewer than `MIN_STAIR_LEN` values on average
+ */
+pub fn auto_stairs(len: usize, key_range: u64, memory: usize) -> usize {
+    let by_length = len / VALUES_PER_STAIR;
+    let budget = (memory / 4).max(1) as u128;
+    let by_memory = ((key_range as u128 + 1) * COUNT_BYTES).div_ceil(budget).min(usize::MAX as u128) as usize;
+    by_length.max(by_memory).clamp(1, (len / MIN_STAIR_LEN).max(1))
+}
+
+/**
+ * sorts a slice using `stairs` bins, any number of stairs gives the same result, `AUTO` (0) picks the number
+ * with `auto_stairs`
+ */
+pub fn sort<T: RadixKey + Clone>(v: &mut [T], stairs: usize) {
+    sort_by_key(v, stairs, |value| value.radix_key());
+}
+
+/**
+ * sorts a slice by a key taken from every value, values with equal keys keep their order
+ */
+pub fn sort_by_key<T: Clone, K: Fn(&T) -> u64>(v: &mut [T], stairs: usize, key: K) {
+    if v.len() < 2 {
+        return;
+    }
+    let min = v.iter().map(&key).min().unwrap_or(0);
+    let max = v.iter().map(&key).max().unwrap_or(0);
+    let stairs = match stairs {
+        AUTO => auto_stairs(v.len(), max - min, available_memory().unwrap_or(FALLBACK_MEMORY)),
+        stairs => stairs,
+    };
+    let bins = Bins::init(v, stairs, (min, max), &key);
+    bins.sort_into(v, &key);
+}
+
+/**
+ * sorts the whole slice as a single bin with one strategy, for comparing the strategies against each other
+ *
+ * `Strategy::Count` allocates a count for every key between the smallest and largest value
+ */
+pub fn sort_with_strategy<T: RadixKey + Clone>(v: &mut [T], strategy: Strategy) {
+    let key = |value: &T| value.radix_key();
+    let min = v.iter().map(key).min().unwrap_or(0);
+    let max = v.iter().map(key).max().unwrap_or(0);
+    let mut values = v.to_vec();
+    match strategy {
+        Strategy::Count => count_sort(&values, v, min, max, &key),
+        Strategy::Radix => radix_sort(&mut values, v, min, max, &key),
+        Strategy::Compare => v.sort_by_key(key),
+    }
+}
+
+/**
+ * the number of threads `par_sort` uses, one per core
+ */
+pub fn thread_count() -> usize {
+    thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+/**
+ * `sort`, with the values partitioned and every bin sorted on worker threads, one per core
+ */
+pub fn par_sort<T: RadixKey + Clone + Send + Sync>(v: &mut [T], stairs: usize) {
+    par_sort_by_key(v, stairs, |value| value.radix_key());
+}
+
+/**
+ * `sort_by_key`, with the values partitioned and every bin sorted on worker threads, one per core
+ *
+ * `AUTO` makes at least 4 stairs per thread so the threads stay busy when the bins aren't even, any other
+ * number of stairs is used as is. inputs too small to be worth splitting up are sorted on the calling thread
+ */
+pub fn par_sort_by_key<T: Clone + Send + Sync, K: Fn(&T) -> u64 + Sync>(v: &mut [T], stairs: usize, key: K) {
+    par_sort_on(thread_count(), v, stairs, key);
+}
+
+/**
+ * `par_sort_by_key` on a given number of threads
+ */
+pub(crate) fn par_sort_on<T: Clone + Send + Sync, K: Fn(&T) -> u64 + Sync>(threads: usize, v: &mut [T], stairs: usize, key: K) {
+    if v.len() < 2 || threads == 1 || v.len() < threads * MIN_STAIR_LEN {
+        return sort_by_key(v, stairs, key);
+    }
+    let key = &key;
+    let min = v.iter().map(key).min().unwrap_or(0);
+    let max = v.iter().map(key).max().unwrap_or(0);
+    let bin_count = match stairs {
+        AUTO => auto_stairs(v.len(), max - min, available_memory().unwrap_or(FALLBACK_MEMORY))
+            .max(threads * 4)
+            .min((v.len() / MIN_STAIR_LEN).max(1)),
+        stairs => stairs,
+    };
+
+    //every thread splits its own chunk of the values into bins
+    let chunk_len = v.len().div_ceil(threads);
+    let pieces: Vec<Vec<Vec<T>>> = thread::scope(|scope| {
+        let workers: Vec<_> = v
+            .chunks(chunk_len)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let mut bins: Vec<Vec<T>> = vec![Vec::new(); bin_count];
+                    for value in chunk {
+                        bins[bin_index(key(value), (min, max), bin_count)].push(value.clone());
                     }
-                    stair_bins[best_bin].push(*i/*v.base_vector.pop()*/);
-                }
-                v.base_vector.clear();
-                stair_bins
-            },
+                    bins
+                })
+            })
+            .collect();
+        workers.into_iter().map(|worker| worker.join().expect("a partitioning thread panicked")).collect()
+    });
+
+    //a bin's pieces, in the order of the chunks they came from so equal keys keep their order, and where it goes in v
+    let mut jobs = Vec::with_capacity(bin_count);
+    let mut rest = v;
+    let mut pieces: Vec<_> = pieces.into_iter().map(|bins| bins.into_iter()).collect();
+    for _ in 0..bin_count {
+        let bin: Vec<Vec<T>> = pieces.iter_mut().map(|bins| bins.next().unwrap_or_default()).collect();
+        let len = bin.iter().map(|piece| piece.len()).sum();
+        let (out, remaining) = rest.split_at_mut(len);
+        rest = remaining;
+        jobs.push((bin, out));
+    }
+
+    //every thread takes the next bin until they're all sorted, straight into v
+    let jobs = Mutex::new(jobs);
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                let job = jobs.lock().expect("a sorting thread panicked").pop();
+                let Some((bin, out)) = job else { break };
+                let mut values = bin.concat();
+                sort_bin(&mut values, out, key);
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::{prop_assert_eq, proptest};
+    use rand::Rng;
+
+    #[test]
+    fn every_bin_strategy_sorts() {
+        assert_eq!(Strategy::pick(100, 50), Strategy::Count);
+        assert_eq!(Strategy::pick(100, 1 << 40), Strategy::Radix);
+        assert_eq!(Strategy::pick(10, 1 << 40), Strategy::Compare);
+
+        //dense, sparse and small sparse inputs, with a single stair so the whole input is one bin
+        let mut rng = crate::test_rng(12345);
+        for (len, modulo) in [(10_000, 1_000), (10_000, u64::MAX), (20, u64::MAX)] {
+            let mut v: Vec<u64> = (0..len).map(|_| rng.gen_range(0..modulo)).collect();
+            let mut expected = v.clone();
+            expected.sort();
+            sort(&mut v, 1);
+            assert_eq!(v, expected);
         }
     }
 
-    //optomized count sort using hashmaps to count
-    fn sort_bins_optimized(&mut self, v: &mut VectorInfo) {
-        //go through the bins
-        for bin in &mut self.bins {
-            //find the min and max values of the bin, and count
-            let mut bin_start_value: usize = 0;
-            let mut bin_end_value: usize = 0;
-            let mut bin_map = HashMap::new();
-            for i in bin.iter() {
-                //min and max
-                if *i > bin_end_value {
-                    bin_end_value = *i;
-                } else if *i < bin_start_value {
-                    bin_start_value = *i;
-                }
-                //build hashmap
-                *bin_map.entry(i).or_insert(0) += 1;//count occurances
-            }
+    #[test]
+    fn parallel_sort_matches_sequential() {
+        let mut rng = crate::test_rng(99);
+        //enough values that every thread gets some, on more threads than there might be cores
+        let threads = 4;
+        let len = threads * MIN_STAIR_LEN * 4;
+        let records: Vec<(i32, usize)> = (0..len).map(|i| (rng.gen_range(-1000..=1000), i)).collect();
 
-            //build sorted vector
-            //averages out to O(2n)
-            for i in bin_start_value..=bin_end_value {
-                let mut value = match bin_map.get(&i) {
-                    Some(x) => *x,
-                    _ => 0,
-                };
-                while 0<value {
-                    //tmp_arr.push(i);
-                    v.sorted_vector.push(i);//+v.min);
-                    value -=1;
-                }
-            }
-            // trouble shooting stuff
-            //println!("bin: {:?}", bin);
-            //println!("bin hash map: {:?}", bin_map);
-            //println!(/*"sorted array so far: */"{:?}", v.sorted_vector);
-            //println!("___");
-            (*bin).clear();      
+        let mut expected = records.clone();
+        expected.sort_by_key(|record| record.0);
+        for stairs in [AUTO, 1, 7, 500] {
+            let mut v = records.clone();
+            par_sort_on(threads, &mut v, stairs, |record| record.0.radix_key());
+            assert_eq!(v, expected);
         }
+
+        let mut wide: Vec<u64> = (0..len).map(|_| rng.gen()).collect();
+        let mut expected = wide.clone();
+        expected.sort();
+        par_sort(&mut wide, AUTO);
+        assert_eq!(wide, expected);
     }
-    /*
-    fn sort_bins(&mut self, v: &mut VectorInfo) {
-        //go through the bins
-        for bin in &mut self.bins {
-            //find the min and max values of the bin
-            let mut bin_start_value: usize = 0;
-            let mut bin_end_value: usize = 0;
-            for i in bin.iter() {
-                let bin_val = *i;
-                if bin_val > bin_end_value {
-                    bin_end_value = bin_val;
-                } else if bin_val < bin_start_value {
-                    bin_start_value = bin_val;
-                }
-            }
-            let range: usize = bin_end_value - bin_start_value + 1;
-
-            //2 auxillary vectors
-            let mut count_arr: Vec<usize> = Vec::new();
-            let mut tmp_arr: Vec<usize>   = Vec::new();
-            //populate the auxillary vectors
-            for _i in 0..range {count_arr.push(0)};
-            for _i in 0..bin.len() {tmp_arr.push(0)};
-
-            //count ocurances of each number in the arr
-            for i in bin.iter() {count_arr[i - bin_start_value] += 1;}
-            for i in 1..count_arr.len() {count_arr[i] += count_arr[i-1];}
-
-            //build tmp_arr with count_arr then apply it to the bin
-            for i in bin.iter().rev() {
-                let bin_val = *i;
-                tmp_arr[count_arr[bin_val - bin_start_value] -1] = bin_val;
-                count_arr[bin_val - bin_start_value] -= 1;
-            }
 
-            //build sorted array
-            for i in &tmp_arr {v.sorted_vector.push(i + v.min);}
-            bin.clear();
-            count_arr.clear();
-            tmp_arr.clear();
+    proptest! {
+        #[test]
+        fn stair_sort_matches_std_sort(mut v: Vec<i64>, stairs in 0usize..300) {
+            let mut expected = v.clone();
+            expected.sort();
+            sort(&mut v, stairs);
+            prop_assert_eq!(v, expected);
         }
+
+        #[test]
+        fn stair_sort_is_stable(mut v: Vec<(u8, u16)>, stairs in 1usize..50) {
+            let mut expected = v.clone();
+            expected.sort_by_key(|pair| pair.0);
+            sort_by_key(&mut v, stairs, |pair| pair.0 as u64);
+            prop_assert_eq!(v, expected);
+        }
+    }
+
+    #[test]
+    fn stair_sort_handles_extremes() {
+        let mut v = vec![i64::MAX, i64::MIN, 0, -1, 1, i64::MIN];
+        sort(&mut v, 3);
+        assert_eq!(v, vec![i64::MIN, i64::MIN, -1, 0, 1, i64::MAX]);
+
+        let mut v = vec![u64::MAX, 0, u64::MAX / 2];
+        sort(&mut v, 1000);
+        assert_eq!(v, vec![0, u64::MAX / 2, u64::MAX]);
+
+        let mut v = vec![2.5, -0.0, -7.25, 1e300, f64::NEG_INFINITY];
+        sort(&mut v, 2);
+        assert_eq!(v, vec![f64::NEG_INFINITY, -7.25, -0.0, 2.5, 1e300]);
+
+        let mut empty: Vec<u8> = Vec::new();
+        sort(&mut empty, 0);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn auto_stairs_follow_length_range_and_memory() {
+        const GIB: usize = 1 << 30;
+        //one stair per million values
+        assert_eq!(auto_stairs(10_000_000, 1_000_000, GIB), 10);
+        assert_eq!(auto_stairs(10, 1_000_000, GIB), 1);
+        //a range too wide to count in a quarter of the memory is split up more
+        assert_eq!(auto_stairs(10_000_000, (1 << 30) - 1, GIB), 32);
+        //but never into stairs that are almost empty
+        assert_eq!(auto_stairs(10_000, u64::MAX, GIB), 10_000 / MIN_STAIR_LEN);
     }
-    */
-}
-pub fn run(v: Vec<usize>, mut stair: usize) -> Vec<usize> {
-    if stair <= 1 {
-        stair = 1;
-    }    
-    let mut vector = VectorInfo::init(v.clone());
-
-    let sys_time_before_sort = SystemTime::now();
-    //find the min and max values
-    vector.min_max();
-
-    let sys_time_after_sort = SystemTime::now();
-    let difference = sys_time_after_sort.duration_since(sys_time_before_sort).expect("time may have gone backwards");
-    println!("mins and max's found in {} seconds!", difference.as_secs_f32());
-    
-    //scale the array such that the min is zero
-    //vector.gen_scaled();
-    //vector.max -= vector.min;
-
-    let sys_time_before_sort = SystemTime::now();
-    //generate the bins
-    let mut bins = Bins::init(stair, &mut vector);
-
-    let sys_time_after_sort = SystemTime::now();
-    let difference = sys_time_after_sort.duration_since(sys_time_before_sort).expect("time may have gone backwards");
-    println!("bins generated in {} seconds!", difference.as_secs_f32());
-    let sys_time_before_sort = SystemTime::now();
-    //sort the bins, also generates the sorted array
-    bins.sort_bins_optimized(&mut vector);
-    
-    let sys_time_after_sort = SystemTime::now();
-    let difference = sys_time_after_sort.duration_since(sys_time_before_sort).expect("time may have gone backwards");
-    println!("bins sorted in {} seconds!", difference.as_secs_f32());
-
-    vector.sorted_vector.clone()
 }
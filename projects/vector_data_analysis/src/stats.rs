@@ -0,0 +1,337 @@
+//! descriptive statistics over slices of any primitive number type
+//!
+//! every function returns None (or an empty result) for an empty slice instead of dividing by zero
+
+use std::cmp::Ordering;
+use std::fmt;
+
+/// the primitive number types statistics can be taken of
+pub trait Number: Copy + PartialOrd + fmt::Debug {
+    fn to_f64(self) -> f64;
+}
+macro_rules! impl_number {
+    ($($t:ty),*) => {
+        $(impl Number for $t {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        })*
+    };
+}
+impl_number!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+/**
+ * orders two numbers, treating the ones that can't be compared (NaN) as equal
+ */
+fn compare<T: Number>(a: &T, b: &T) -> Ordering {
+    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+}
+
+/**
+ * a sorted copy of the slice
+ */
+pub fn sorted<T: Number>(v: &[T]) -> Vec<T> {
+    let mut sorted = v.to_vec();
+    sorted.sort_by(compare);
+    sorted
+}
+
+/**
+ * the sum of every value, using compensated (Neumaier) summation so rounding errors don't build up
+ */
+pub fn sum<T: Number>(v: &[T]) -> f64 {
+    let mut total = 0.0;
+    let mut compensation = 0.0;
+    for value in v.iter().map(|x| x.to_f64()) {
+        let next = total + value;
+        //keep the low order bits that got lost adding the smaller of the two
+        if f64::abs(total) >= f64::abs(value) {
+            compensation += (total - next) + value;
+        } else {
+            compensation += (value - next) + total;
+        }
+        total = next;
+    }
+    total + compensation
+}
+
+/**
+ * the average value, the division happens in floating point so nothing is truncated
+ */
+pub fn mean<T: Number>(v: &[T]) -> Option<f64> {
+    if v.is_empty() {
+        return None;
+    }
+    Some(sum(v) / v.len() as f64)
+}
+
+/**
+ * the smallest and largest values
+ */
+pub fn min_max<T: Number>(v: &[T]) -> Option<(T, T)> {
+    let first = *v.first()?;
+    Some(v.iter().fold((first, first), |(min, max), x| {
+        (if compare(x, &min) == Ordering::Less {*x} else {min}, if compare(x, &max) == Ordering::Greater {*x} else {max})
+    }))
+}
+
+/**
+ * the middle value, or the average of the two middle values when there's an even number of them
+ */
+pub fn median<T: Number>(v: &[T]) -> Option<f64> {
+    percentile_of_sorted(&sorted(v), 50.0)
+}
+
+/**
+ * the most common values, in ascending order. every value ties when none repeat
+ */
+pub fn modes<T: Number>(v: &[T]) -> Vec<T> {
+    modes_of_sorted(&sorted(v))
+}
+
+/**
+ * the population variance, the average squared distance from the mean
+ */
+pub fn variance<T: Number>(v: &[T]) -> Option<f64> {
+    let mean = mean(v)?;
+    let squares: Vec<f64> = v.iter().map(|x| (x.to_f64() - mean).powi(2)).collect();
+    Some(sum(&squares) / v.len() as f64)
+}
+
+/**
+ * the sample variance, with Bessel's correction, needs at least 2 values
+ */
+pub fn sample_variance<T: Number>(v: &[T]) -> Option<f64> {
+    if v.len() < 2 {
+        return None;
+    }
+    Some(variance(v)? * v.len() as f64 / (v.len() - 1) as f64)
+}
+
+/// the population standard deviation
+pub fn std_dev<T: Number>(v: &[T]) -> Option<f64> {
+    variance(v).map(f64::sqrt)
+}
+
+/// the sample standard deviation, needs at least 2 values
+pub fn sample_std_dev<T: Number>(v: &[T]) -> Option<f64> {
+    sample_variance(v).map(f64::sqrt)
+}
+
+/**
+ * the value p percent of the way through the data, 0 to 100, interpolating between the two closest values
+ */
+pub fn percentile<T: Number>(v: &[T], p: f64) -> Option<f64> {
+    percentile_of_sorted(&sorted(v), p)
+}
+
+/**
+ * `percentile` for data that's already sorted, so it can be called many times without sorting again
+ */
+pub fn percentile_of_sorted<T: Number>(sorted: &[T], p: f64) -> Option<f64> {
+    if sorted.is_empty() || !(0.0..=100.0).contains(&p) {
+        return None;
+    }
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let (below, above) = (rank.floor() as usize, rank.ceil() as usize);
+    let (low, high) = (sorted[below].to_f64(), sorted[above].to_f64());
+    Some(low + (high - low) * (rank - below as f64))
+}
+
+/**
+ * `modes` for data that's already sorted, equal values are next to each other so they're counted in runs
+ */
+pub fn modes_of_sorted<T: Number>(sorted: &[T]) -> Vec<T> {
+    let mut modes = Vec::new();
+    let mut best = 0;
+    let mut start = 0;
+    while start < sorted.len() {
+        let run = sorted[start..].iter().take_while(|x| compare(*x, &sorted[start]) == Ordering::Equal).count();
+        match run.cmp(&best) {
+            Ordering::Greater => {
+                best = run;
+                modes.clear();
+                modes.push(sorted[start]);
+            }
+            Ordering::Equal => modes.push(sorted[start]),
+            Ordering::Less => {}
+        }
+        start += run;
+    }
+    modes
+}
+
+/// how many values fall in each of a number of equal width buckets between the smallest and largest value
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    pub min: f64,
+    pub max: f64,
+    pub counts: Vec<usize>,
+}
+impl Histogram {
+    /**
+     * counts the values into `buckets` buckets, the largest value goes in the last bucket
+     */
+    pub fn new<T: Number>(v: &[T], buckets: usize) -> Histogram {
+        let buckets = buckets.max(1);
+        let (min, max) = match min_max(v) {
+            Some((min, max)) => (min.to_f64(), max.to_f64()),
+            None => return Histogram { min: 0.0, max: 0.0, counts: vec![0; buckets] },
+        };
+        let mut counts = vec![0; buckets];
+        for x in v {
+            counts[bucket_of(x.to_f64(), min, max, buckets)] += 1;
+        }
+        Histogram { min, max, counts }
+    }
+
+    /// the width of every bucket
+    pub fn bucket_width(&self) -> f64 {
+        (self.max - self.min) / self.counts.len() as f64
+    }
+
+    /// the (start, end) of a bucket
+    pub fn bucket_range(&self, bucket: usize) -> (f64, f64) {
+        let width = self.bucket_width();
+        (self.min + width * bucket as f64, self.min + width * (bucket + 1) as f64)
+    }
+}
+
+/**
+ * which of `buckets` equal width buckets between min and max a value falls in, found in O(1). max goes in the last
+ * bucket
+ */
+pub(crate) fn bucket_of(x: f64, min: f64, max: f64, buckets: usize) -> usize {
+    if max <= min {
+        return 0;
+    }
+    (((x - min) / (max - min) * buckets as f64).floor() as usize).min(buckets - 1)
+}
+
+/// every statistic of a data set, computed from one sorted copy of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub median: f64,
+    pub modes: Vec<f64>,
+    pub variance: f64,
+    pub std_dev: f64,
+    /// (p, value) for every percentile asked for
+    pub percentiles: Vec<(f64, f64)>,
+    pub histogram: Histogram,
+}
+impl Summary {
+    /**
+     * computes every statistic, None if there's no data
+     */
+    pub fn new<T: Number>(v: &[T], percentiles: &[f64], buckets: usize) -> Option<Summary> {
+        let sorted = sorted(v);
+        let (min, max) = (sorted.first()?.to_f64(), sorted.last()?.to_f64());
+        let variance = variance(v)?;
+        Some(Summary {
+            count: v.len(),
+            min,
+            max,
+            mean: mean(v)?,
+            median: percentile_of_sorted(&sorted, 50.0)?,
+            modes: modes_of_sorted(&sorted).iter().map(|x| x.to_f64()).collect(),
+            variance,
+            std_dev: variance.sqrt(),
+            percentiles: percentiles.iter().filter_map(|p| Some((*p, percentile_of_sorted(&sorted, *p)?))).collect(),
+            histogram: Histogram::new(&sorted, buckets),
+        })
+    }
+}
+impl fmt::Display for Summary {
+    /**
+     * a report of every statistic, one per line
+     */
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "count:    {}", self.count)?;
+        writeln!(f, "min:      {}", self.min)?;
+        writeln!(f, "max:      {}", self.max)?;
+        writeln!(f, "mean:     {}", self.mean)?;
+        writeln!(f, "median:   {}", self.median)?;
+        //a mode only means something when values repeat
+        if self.modes.len() == self.count && self.count > 1 {
+            writeln!(f, "mode:     none, every value is unique")?;
+        } else {
+            let modes: Vec<String> = self.modes.iter().map(|m| m.to_string()).collect();
+            writeln!(f, "mode:     {}", modes.join(", "))?;
+        }
+        writeln!(f, "variance: {}", self.variance)?;
+        writeln!(f, "std dev:  {}", self.std_dev)?;
+        for (p, value) in &self.percentiles {
+            writeln!(f, "p{:<8}{}", format!("{}:", p), value)?;
+        }
+        writeln!(f, "histogram:")?;
+        for (bucket, count) in self.histogram.counts.iter().enumerate() {
+            let (start, end) = self.histogram.bucket_range(bucket);
+            writeln!(f, "  [{}, {}{} {}", start, end, if bucket + 1 == self.histogram.counts.len() {"]:"} else {"):"}, count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mean_does_not_truncate() {
+        assert_eq!(mean(&[1usize, 2]), Some(1.5));
+        assert_eq!(mean(&[-3i32, 4]), Some(0.5));
+        assert_eq!(mean::<u8>(&[]), None);
+        //compensated summation keeps small values next to big ones
+        assert_eq!(sum(&[1e16, 1.0, -1e16]), 1.0);
+    }
+
+    #[test]
+    fn median_and_percentiles() {
+        assert_eq!(median(&[5, 1, 3]), Some(3.0));
+        assert_eq!(median(&[4, 1, 3, 2]), Some(2.5));
+        assert_eq!(percentile(&[1.0, 2.0, 3.0, 4.0, 5.0], 25.0), Some(2.0));
+        assert_eq!(percentile(&[10u64, 20], 75.0), Some(17.5));
+        assert_eq!(percentile(&[1], 101.0), None);
+    }
+
+    #[test]
+    fn modes_include_ties() {
+        assert_eq!(modes(&[3, 1, 3, 2, 1]), vec![1, 3]);
+        assert_eq!(modes(&[0.5, 0.5, 2.0]), vec![0.5]);
+        assert!(modes::<i8>(&[]).is_empty());
+    }
+
+    #[test]
+    fn spread() {
+        let v = [2, 4, 4, 4, 5, 5, 7, 9];
+        assert_eq!(variance(&v), Some(4.0));
+        assert_eq!(std_dev(&v), Some(2.0));
+        assert_eq!(sample_variance(&v), Some(32.0 / 7.0));
+        assert_eq!(sample_variance(&[1]), None);
+        assert_eq!(min_max(&[3i64, -7, 12, 0]), Some((-7, 12)));
+    }
+
+    #[test]
+    fn histogram_buckets() {
+        let histogram = Histogram::new(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10], 5);
+        assert_eq!(histogram.counts, vec![2, 2, 2, 2, 3]);
+        assert_eq!(histogram.bucket_range(1), (2.0, 4.0));
+        assert_eq!(Histogram::new(&[7, 7, 7], 3).counts, vec![3, 0, 0]);
+    }
+
+    #[test]
+    fn summary_report() {
+        let summary = Summary::new(&[1, 2, 2, 3], &[50.0], 2).unwrap();
+        assert_eq!((summary.count, summary.mean, summary.median), (4, 2.0, 2.0));
+        assert_eq!(summary.modes, vec![2.0]);
+        let report = summary.to_string();
+        assert!(report.contains("mode:     2\n"));
+        assert!(report.contains("p50:     2\n"));
+        assert!(report.contains("  [2, 3]: 3\n"));
+        assert_eq!(Summary::new::<f32>(&[], &[], 1), None);
+    }
+}
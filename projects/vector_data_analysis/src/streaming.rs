@@ -0,0 +1,472 @@
+//! statistics taken in one pass with bounded memory, for data too big to hold in a vector
+//!
+//! the mean, variance, min and max are exact, the quantiles and most common values are estimates
+
+use crate::stats;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::BufRead;
+
+/**
+ * calls f with every number read from a reader, numbers can be separated by whitespace or commas
+ *
+ * the reader is read a buffer at a time, so only the number being read is held, however long the lines are
+ */
+pub fn for_each_number<R: BufRead, F: FnMut(f64)>(reader: R, mut f: F) -> Result<(), Box<dyn Error>> {
+    for_each_word(reader, |word| {
+        f(parse_number(word)?);
+        Ok(())
+    })
+}
+
+/**
+ * calls f with every word read from a reader, split on whitespace and commas, stopping at the first error
+ */
+pub fn for_each_word<R: BufRead, F: FnMut(&[u8]) -> Result<(), Box<dyn Error>>>(mut reader: R, mut f: F) -> Result<(), Box<dyn Error>> {
+    let mut word = Vec::new();
+    loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            break;
+        }
+        let len = buf.len();
+        for byte in buf {
+            if byte.is_ascii_whitespace() || *byte == b',' {
+                if !word.is_empty() {
+                    f(&word)?;
+                    word.clear();
+                }
+            } else {
+                word.push(*byte);
+            }
+        }
+        reader.consume(len);
+    }
+    if !word.is_empty() {
+        f(&word)?;
+    }
+    Ok(())
+}
+
+/**
+ * parses one number, it has to be finite
+ */
+fn parse_number(token: &[u8]) -> Result<f64, Box<dyn Error>> {
+    let word = String::from_utf8_lossy(token);
+    match word.parse::<f64>() {
+        Ok(number) if number.is_finite() => Ok(number),
+        _ => Err(format!("\"{}\" isn't a number", word).into()),
+    }
+}
+
+/// Welford's running mean and variance, which doesn't lose precision the way summing squares does
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Welford {
+    count: u64,
+    mean: f64,
+    /// sum of the squared distances from the mean
+    m2: f64,
+}
+impl Welford {
+    pub fn new() -> Welford {
+        Welford::default()
+    }
+
+    pub fn push(&mut self, x: f64) {
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        if self.count == 0 {None} else {Some(self.mean)}
+    }
+
+    /// the population variance
+    pub fn variance(&self) -> Option<f64> {
+        if self.count == 0 {None} else {Some(self.m2 / self.count as f64)}
+    }
+
+    /// the sample variance, needs at least 2 values
+    pub fn sample_variance(&self) -> Option<f64> {
+        if self.count < 2 {None} else {Some(self.m2 / (self.count - 1) as f64)}
+    }
+
+    pub fn std_dev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+}
+
+/// the smallest and largest values seen
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MinMax {
+    range: Option<(f64, f64)>,
+}
+impl MinMax {
+    pub fn new() -> MinMax {
+        MinMax::default()
+    }
+
+    pub fn push(&mut self, x: f64) {
+        self.range = Some(match self.range {
+            Some((min, max)) => (min.min(x), max.max(x)),
+            None => (x, x),
+        });
+    }
+
+    pub fn get(&self) -> Option<(f64, f64)> {
+        self.range
+    }
+}
+
+/// a uniform random sample of a fixed size, every value seen is as likely to be in it (algorithm R)
+#[derive(Debug, Clone)]
+pub struct Reservoir {
+    capacity: usize,
+    seen: u64,
+    samples: Vec<f64>,
+    rng: ChaCha8Rng,
+}
+impl Reservoir {
+    /// a reservoir holding up to `capacity` values, the same seed picks the same sample
+    pub fn new(capacity: usize, seed: u64) -> Reservoir {
+        Reservoir { capacity, seen: 0, samples: Vec::with_capacity(capacity), rng: ChaCha8Rng::seed_from_u64(seed) }
+    }
+
+    pub fn push(&mut self, x: f64) {
+        self.seen += 1;
+        if self.samples.len() < self.capacity {
+            self.samples.push(x);
+        } else {
+            //keep the new value with a chance of capacity / seen, in place of a random one
+            let slot = self.rng.gen_range(0..self.seen);
+            if (slot as usize) < self.capacity {
+                self.samples[slot as usize] = x;
+            }
+        }
+    }
+
+    pub fn samples(&self) -> &[f64] {
+        &self.samples
+    }
+}
+
+/// the P² (piecewise parabolic) estimate of one quantile, from 5 markers instead of the values
+#[derive(Debug, Clone, PartialEq)]
+pub struct P2Quantile {
+    /// the quantile, 0 to 1
+    p: f64,
+    /// heights of the markers, the first 5 values until there are 5
+    heights: Vec<f64>,
+    /// actual and desired positions of the markers, and how much the desired ones move for every value
+    positions: [f64; 5],
+    desired: [f64; 5],
+    increments: [f64; 5],
+}
+impl P2Quantile {
+    /// estimates the quantile p, from 0 to 1
+    pub fn new(p: f64) -> P2Quantile {
+        P2Quantile {
+            p,
+            heights: Vec::with_capacity(5),
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * p, 1.0 + 4.0 * p, 3.0 + 2.0 * p, 5.0],
+            increments: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+        }
+    }
+
+    pub fn push(&mut self, x: f64) {
+        if self.heights.len() < 5 {
+            self.heights.push(x);
+            if self.heights.len() == 5 {
+                self.heights.sort_by(|a, b| a.partial_cmp(b).expect("numbers are finite"));
+            }
+            return;
+        }
+
+        //find the cell the value falls in, stretching the ends if it's past them
+        let q = &mut self.heights;
+        let k = if x < q[0] {
+            q[0] = x;
+            0
+        } else if x >= q[4] {
+            q[4] = x;
+            3
+        } else {
+            (0..4).find(|i| x < q[i + 1]).unwrap_or(3)
+        };
+        for position in &mut self.positions[k + 1..] {
+            *position += 1.0;
+        }
+        for (desired, increment) in self.desired.iter_mut().zip(self.increments.iter()) {
+            *desired += increment;
+        }
+
+        //move the middle markers that are too far from where they should be
+        for i in 1..4 {
+            let d = self.desired[i] - self.positions[i];
+            let n = &self.positions;
+            if (d >= 1.0 && n[i + 1] - n[i] > 1.0) || (d <= -1.0 && n[i - 1] - n[i] < -1.0) {
+                let d = d.signum();
+                let parabolic = self.parabolic(i, d);
+                let q = &self.heights;
+                self.heights[i] = if q[i - 1] < parabolic && parabolic < q[i + 1] {parabolic} else {self.linear(i, d)};
+                self.positions[i] += d;
+            }
+        }
+    }
+
+    fn parabolic(&self, i: usize, d: f64) -> f64 {
+        let (q, n) = (&self.heights, &self.positions);
+        q[i] + d / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    fn linear(&self, i: usize, d: f64) -> f64 {
+        let j = if d > 0.0 {i + 1} else {i - 1};
+        self.heights[i] + d * (self.heights[j] - self.heights[i]) / (self.positions[j] - self.positions[i])
+    }
+
+    /// the estimate, exact until there are 5 values and for the smallest and largest value
+    pub fn estimate(&self) -> Option<f64> {
+        if self.heights.len() < 5 {
+            return stats::percentile(&self.heights, self.p * 100.0);
+        }
+        Some(if self.p <= 0.0 {
+            self.heights[0]
+        } else if self.p >= 1.0 {
+            self.heights[4]
+        } else {
+            self.heights[2]
+        })
+    }
+}
+
+/// a count-min sketch, estimates how often every value was seen in a fixed amount of memory, never too low
+#[derive(Debug, Clone, PartialEq)]
+pub struct CountMin {
+    width: usize,
+    /// how many keys were counted
+    total: u64,
+    /// one row of counters per hash
+    rows: Vec<Vec<u64>>,
+}
+impl CountMin {
+    /// `depth` rows of `width` counters, more of either makes the estimates closer
+    pub fn new(width: usize, depth: usize) -> CountMin {
+        CountMin { width: width.max(1), total: 0, rows: vec![vec![0; width.max(1)]; depth.max(1)] }
+    }
+
+    fn slot(&self, row: usize, key: u64) -> usize {
+        let mut hasher = DefaultHasher::new();
+        (row, key).hash(&mut hasher);
+        (hasher.finish() % self.width as u64) as usize
+    }
+
+    /// counts the key, returning its new estimate
+    pub fn add(&mut self, key: u64) -> u64 {
+        self.total += 1;
+        let mut estimate = u64::MAX;
+        for row in 0..self.rows.len() {
+            let slot = self.slot(row, key);
+            self.rows[row][slot] += 1;
+            estimate = estimate.min(self.rows[row][slot]);
+        }
+        estimate
+    }
+
+    pub fn estimate(&self, key: u64) -> u64 {
+        (0..self.rows.len()).map(|row| self.rows[row][self.slot(row, key)]).min().unwrap_or(0)
+    }
+
+    /// how much too high an estimate usually is at most, e * total / width
+    pub fn error_bound(&self) -> u64 {
+        (std::f64::consts::E * self.total as f64 / self.width as f64).ceil() as u64
+    }
+}
+
+/// the values estimated to be the most common, tracked with a count-min sketch
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeavyHitters {
+    sketch: CountMin,
+    /// how many values to keep track of
+    k: usize,
+    /// the best candidates so far, by the bits of the value
+    candidates: HashMap<u64, u64>,
+}
+impl HeavyHitters {
+    pub fn new(k: usize) -> HeavyHitters {
+        HeavyHitters { sketch: CountMin::new(2048, 4), k: k.max(1), candidates: HashMap::new() }
+    }
+
+    pub fn push(&mut self, x: f64) {
+        //-0 and 0 are the same value
+        let key = if x == 0.0 {0.0f64.to_bits()} else {x.to_bits()};
+        let estimate = self.sketch.add(key);
+        if self.candidates.contains_key(&key) || self.candidates.len() < self.k {
+            self.candidates.insert(key, estimate);
+            return;
+        }
+        let (weakest, weakest_count) = self.candidates.iter().min_by_key(|(_, count)| **count).map(|(k, c)| (*k, *c)).expect("k is at least 1");
+        if estimate > weakest_count {
+            self.candidates.remove(&weakest);
+            self.candidates.insert(key, estimate);
+        }
+    }
+
+    /// the candidates with their estimated counts, most common first, leaving out any the sketch can't tell from collisions
+    pub fn top(&self) -> Vec<(f64, u64)> {
+        let bound = self.sketch.error_bound();
+        let mut top: Vec<(f64, u64)> = self.candidates.iter().filter(|(_, count)| **count > bound).map(|(key, count)| (f64::from_bits(*key), *count)).collect();
+        top.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.partial_cmp(&b.0).expect("numbers are finite")));
+        top
+    }
+}
+
+/// every streaming statistic at once
+#[derive(Debug, Clone)]
+pub struct StreamingSummary {
+    pub moments: Welford,
+    pub range: MinMax,
+    pub median: P2Quantile,
+    /// (p, estimate) for every percentile asked for
+    pub percentiles: Vec<(f64, P2Quantile)>,
+    /// a random sample of the values, the plots are drawn from it
+    pub sample: Reservoir,
+    pub heavy_hitters: HeavyHitters,
+}
+impl StreamingSummary {
+    /// how many values are kept for the sample the plots are drawn from, and how many of the most common values are tracked
+    pub const SAMPLE_SIZE: usize = 10_000;
+    pub const HEAVY_HITTERS: usize = 5;
+
+    pub fn new(percentiles: &[f64], seed: u64) -> StreamingSummary {
+        StreamingSummary {
+            moments: Welford::new(),
+            range: MinMax::new(),
+            median: P2Quantile::new(0.5),
+            percentiles: percentiles.iter().map(|p| (*p, P2Quantile::new(p / 100.0))).collect(),
+            sample: Reservoir::new(Self::SAMPLE_SIZE, seed),
+            heavy_hitters: HeavyHitters::new(Self::HEAVY_HITTERS),
+        }
+    }
+
+    pub fn push(&mut self, x: f64) {
+        self.moments.push(x);
+        self.range.push(x);
+        self.median.push(x);
+        for (_, quantile) in &mut self.percentiles {
+            quantile.push(x);
+        }
+        self.sample.push(x);
+        self.heavy_hitters.push(x);
+    }
+}
+impl fmt::Display for StreamingSummary {
+    /**
+     * a report of every statistic, one per line, the estimates are marked with ~
+     */
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (min, max) = match self.range.get() {
+            Some(range) => range,
+            None => return writeln!(f, "count:    0"),
+        };
+        writeln!(f, "count:    {}", self.moments.count())?;
+        writeln!(f, "min:      {}", min)?;
+        writeln!(f, "max:      {}", max)?;
+        writeln!(f, "mean:     {}", self.moments.mean().unwrap_or_default())?;
+        writeln!(f, "median:  ~{}", self.median.estimate().unwrap_or_default())?;
+        let top: Vec<String> = self.heavy_hitters.top().iter().map(|(value, count)| format!("{} (~{})", value, count)).collect();
+        if top.is_empty() {
+            writeln!(f, "common:   none stand out")?;
+        } else {
+            writeln!(f, "common:   {}", top.join(", "))?;
+        }
+        writeln!(f, "variance: {}", self.moments.variance().unwrap_or_default())?;
+        writeln!(f, "std dev:  {}", self.moments.std_dev().unwrap_or_default())?;
+        for (p, quantile) in &self.percentiles {
+            writeln!(f, "p{:<7}~{}", format!("{}:", p), quantile.estimate().unwrap_or_default())?;
+        }
+        writeln!(f, "sample:   {} of {} values kept", self.sample.samples().len(), self.moments.count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    fn streaming_statistics_match_exact_ones() {
+        let mut rng = crate::test_rng(7);
+        //uniform values from 0 to 1000, with 42 far more common than the rest
+        let v: Vec<f64> = (0..50_000).map(|i| if i % 10 == 0 {42.0} else {rng.gen_range(0..=1_000_000) as f64 / 1000.0}).collect();
+        let mut summary = StreamingSummary::new(&[10.0, 99.0], 3);
+        for x in &v {
+            summary.push(*x);
+        }
+
+        assert_eq!(summary.moments.count(), v.len() as u64);
+        assert!((summary.moments.mean().unwrap() - stats::mean(&v).unwrap()).abs() < 1e-9);
+        assert!((summary.moments.variance().unwrap() - stats::variance(&v).unwrap()).abs() < 1e-6);
+        assert_eq!(summary.range.get(), stats::min_max(&v));
+        //the estimates are within half a percent of the range
+        for (p, quantile) in summary.percentiles.iter().chain([(50.0, summary.median.clone())].iter()) {
+            assert!((quantile.estimate().unwrap() - stats::percentile(&v, *p).unwrap()).abs() < 5.0, "p{}", p);
+        }
+        assert_eq!(summary.heavy_hitters.top()[0].0, 42.0);
+        assert!(summary.heavy_hitters.top()[0].1 >= 5000);
+        assert_eq!(summary.sample.samples().len(), StreamingSummary::SAMPLE_SIZE);
+        assert!(summary.to_string().contains("common:   42 (~"));
+        //values that don't repeat can't be told from hash collisions
+        assert_eq!(summary.heavy_hitters.top().len(), 1);
+    }
+
+    #[test]
+    fn streaming_reads_numbers_across_buffers() {
+        //a tiny buffer splits numbers between reads
+        let input = "1, 22.5\n-333\t4e2,,5";
+        let mut numbers = Vec::new();
+        for_each_number(io::BufReader::with_capacity(3, input.as_bytes()), |x| numbers.push(x)).unwrap();
+        assert_eq!(numbers, crate::read_numbers(input).unwrap());
+        assert!(for_each_number("1 two".as_bytes(), |_| {}).is_err());
+    }
+
+    #[test]
+    fn streaming_handles_few_values() {
+        let mut welford = Welford::new();
+        assert_eq!(welford.mean(), None);
+        for x in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+            welford.push(x);
+        }
+        assert_eq!((welford.mean(), welford.std_dev()), (Some(5.0), Some(2.0)));
+        assert_eq!(welford.sample_variance(), Some(32.0 / 7.0));
+
+        let mut median = P2Quantile::new(0.5);
+        for x in [3.0, 1.0, 2.0] {
+            median.push(x);
+        }
+        assert_eq!(median.estimate(), Some(2.0));
+
+        //the same seed keeps the same sample
+        let mut a = Reservoir::new(3, 1);
+        let mut b = Reservoir::new(3, 1);
+        for x in 0..100 {
+            a.push(x as f64);
+            b.push(x as f64);
+        }
+        assert_eq!(a.samples(), b.samples());
+        assert_eq!(StreamingSummary::new(&[], 0).to_string(), "count:    0\n");
+    }
+}
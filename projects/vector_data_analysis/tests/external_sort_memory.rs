@@ -0,0 +1,29 @@
+//! the external sort stays within its memory limit, measured with the tracking allocator
+//!
+//! this is its own test binary so the allocator only counts the sort, not tests running alongside it
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::io;
+use vector_data_analysis::external_sort::{self, Options};
+use vector_data_analysis::memory;
+
+#[global_allocator]
+static GLOBAL: memory::TrackingAllocator = memory::TrackingAllocator;
+
+#[test]
+fn external_sort_stays_within_its_memory_limit() {
+    let mut rng = ChaCha8Rng::seed_from_u64(45);
+    let args: Vec<String> = ["in", "out", "--format=binary", "--memory=1"].iter().map(|s| s.to_string()).collect();
+    let options = Options::new(&args).unwrap();
+    //dense values get count sorted, sparse ones radix sorted
+    let dense: Vec<u8> = (0..300_000).flat_map(|_| rng.gen_range(0..1000i64).to_le_bytes()).collect();
+    let sparse: Vec<u8> = (0..300_000).flat_map(|_| rng.gen::<i64>().to_le_bytes()).collect();
+
+    for input in [dense, sparse].iter() {
+        let (stats, peak) = memory::peak_during(|| external_sort::sort_stream(input.as_slice(), io::sink(), &options).unwrap());
+        assert!(memory::is_tracking());
+        assert!(stats.runs > 1);
+        assert!(peak <= options.memory, "peaked at {} bytes with a limit of {}", peak, options.memory);
+    }
+}
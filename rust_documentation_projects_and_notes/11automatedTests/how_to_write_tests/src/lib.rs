@@ -128,6 +128,11 @@ mod tests {
         assert_eq!(vec!(0,1,2,2,4,6,7,8,9), _count_sort(&v));
     }
     #[test]
+    fn it_sorts_from_the_min() {
+        let v: Vec<usize> = vec!(1_000_005,1_000_001,1_000_003,1_000_001);
+        assert_eq!(vec!(1_000_001,1_000_001,1_000_003,1_000_005), _count_sort(&v));
+    }
+    #[test]
     fn does_it_add_two() {
         assert_eq!(4, bad_add_two(2));
     }
@@ -256,37 +261,10 @@ impl Rectangle {
     }
 }
 
-use std::collections::HashMap;
+//the counting sort is shared with the other projects, this sorts a copy counting up from the smallest value
 pub fn _count_sort (v: &[usize]) -> Vec<usize> {
-    let mut sorted_vector = Vec::new();
-    
-    //find the min and max values of the bin, and count
-    let mut start_value: usize = 0;
-    let mut end_value: usize = 0;
-    let mut vec_map = HashMap::new();
-    for i in v.iter() {
-        //min and max
-        if *i > end_value {
-            end_value = *i;
-        } else if *i < start_value {
-            start_value = *i;
-        }
-        //build hashmap
-        *vec_map.entry(i).or_insert(0) += 1; //count occurances
-    }
-
-    //build sorted vector
-    for i in start_value..=end_value {
-        let mut value = match vec_map.get(&i) {
-            Some(x) => *x,
-            _ => 0,
-        };
-        while 0 < value {
-            sorted_vector.push(i);
-            value -= 1;
-        }
-    }
-
+    let mut sorted_vector = v.to_vec();
+    counting_sort::count_sort(&mut sorted_vector);
     sorted_vector
 }
 
@@ -0,0 +1,180 @@
+//packages and whatnot
+use counting_sort::{count_sort, count_sort_vanilla, min_max, radix_sort_by_key, Integer};
+use rand::distributions::{Distribution, Uniform};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::error::Error;
+use std::time::SystemTime;
+
+const DEFAULT_SIZE: usize = 10_000_000; //how many numbers to sort when no size is given, each copy is 80MB
+const DEFAULT_MAX: i64 = 32767; //numbers are between min and max, inclusive
+const MAX_RANGE: u64 = 1 << 28; //widest range of numbers counted, the count array has one 8 byte slot for each number in it
+
+/*
+ * the sorting algorithms that can be picked from the command line
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Count,        //counts every number in a hashmap
+    CountVanilla, //counts every number in an array, then places them with cumulative counts
+    Radix,        //LSD radix sort, one counting sort per byte
+    Std,          //the standard library's sort_unstable, to compare against
+}
+impl Algorithm {
+    pub const ALL: [Algorithm; 4] = [Algorithm::Count, Algorithm::CountVanilla, Algorithm::Radix, Algorithm::Std];
+
+    pub fn parse(name: &str) -> Result<Algorithm, &'static str> {
+        match name {
+            "count" => Ok(Algorithm::Count),
+            "vanilla" => Ok(Algorithm::CountVanilla),
+            "radix" => Ok(Algorithm::Radix),
+            "std" => Ok(Algorithm::Std),
+            _ => Err("unknown algorithm, expected count, vanilla, radix, std or all"),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Count => "count",
+            Algorithm::CountVanilla => "vanilla",
+            Algorithm::Radix => "radix",
+            Algorithm::Std => "std",
+        }
+    }
+
+    pub fn sort<T: Integer>(&self, arr: &mut [T]) {
+        match self {
+            Algorithm::Count => count_sort(arr),
+            Algorithm::CountVanilla => count_sort_vanilla(arr),
+            Algorithm::Radix => {
+                if let Some((min, _)) = min_max(arr) {
+                    radix_sort_by_key(arr, |x| x.offset_from(min) as u64);
+                }
+            }
+            Algorithm::Std => arr.sort_unstable(),
+        }
+    }
+}
+
+pub struct Config {
+    pub size: usize,
+    pub min: i64,
+    pub max: i64,
+    pub algorithms: Vec<Algorithm>,
+    pub seed: Option<u64>, //a random seed is picked (and printed) when none is given
+}
+impl Config {
+    /*
+     * parses the passed args (taken from command line)
+     *
+     * usage: array_sorting [--size=<n>] [--min=<n>] [--max=<n>] [--algorithm=<count|vanilla|radix|std|all>] [--seed=<n>]
+     */
+    pub fn new(args: &[String]) -> Result<Config, &str> {
+        //DATA
+        let mut size = DEFAULT_SIZE;
+        let mut min = 0;
+        let mut max = DEFAULT_MAX;
+        let mut algorithms = vec![Algorithm::CountVanilla];
+        let mut seed = None;
+
+        //the first arg is the program name
+        for arg in args.iter().skip(1) {
+            if let Some(value) = arg.strip_prefix("--size=") {
+                size = value.parse().map_err(|_| "size must be a whole number")?;
+            } else if let Some(value) = arg.strip_prefix("--min=") {
+                min = value.parse().map_err(|_| "min must be a whole number")?;
+            } else if let Some(value) = arg.strip_prefix("--max=") {
+                max = value.parse().map_err(|_| "max must be a whole number")?;
+            } else if let Some(value) = arg.strip_prefix("--algorithm=") {
+                algorithms = if value == "all" {Algorithm::ALL.to_vec()} else {vec![Algorithm::parse(value)?]};
+            } else if let Some(value) = arg.strip_prefix("--seed=") {
+                seed = Some(value.parse().map_err(|_| "seed must be a whole number")?);
+            } else {
+                return Err("unknown argument");
+            }
+        }
+
+        if min > max {
+            return Err("min can't be bigger than max");
+        }
+        if max.offset_from(min) as u64 >= MAX_RANGE {
+            return Err("the range is too wide to count, max - min must be less than 2^28");
+        }
+        Ok(Config { size, min, max, algorithms, seed })
+    }
+}
+
+pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
+    //DATA
+    let seed = config.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    //fill a vector on the heap with random numbers between min and max
+    println!("generating {} numbers between {} and {} with seed {}", config.size, config.min, config.max, seed);
+    let numbers: Vec<i64> = Uniform::from(config.min..=config.max).sample_iter(&mut rng).take(config.size).collect();
+    let expected = checksum(&numbers);
+
+    //sort a copy with every algorithm, checking each one got it right, only one copy is around at a time
+    for algorithm in &config.algorithms {
+        let mut arr = numbers.clone();
+        let sys_time_before_sort = SystemTime::now();
+        algorithm.sort(&mut arr);
+        let sys_time_after_sort = SystemTime::now();
+        println!("{} sorted in {} seconds", algorithm.name(), sys_time_after_sort.duration_since(sys_time_before_sort).expect("time may have gone backwards").as_secs_f32());
+
+        if !arr.windows(2).all(|w| w[0] <= w[1]) || checksum(&arr) != expected {
+            return Err(format!("{} didn't sort the numbers correctly", algorithm.name()).into());
+        }
+    }
+    Ok(())
+}//run
+
+/*
+ * the sums of the numbers and of their squares, they stay the same however the numbers are ordered,
+ * so a sorted copy with the same checksum almost certainly has the same numbers
+ */
+fn checksum(arr: &[i64]) -> (i128, i128) {
+    arr.iter().fold((0, 0), |(sum, squares), x| {
+        let x = *x as i128;
+        (sum.wrapping_add(x), squares.wrapping_add(x * x))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counting_sorts_match_std() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let numbers: Vec<i64> = Uniform::from(-500..=500).sample_iter(&mut rng).take(10_000).collect();
+        let mut expected = numbers.clone();
+        expected.sort();
+        for algorithm in Algorithm::ALL.iter() {
+            let mut arr = numbers.clone();
+            algorithm.sort(&mut arr);
+            assert_eq!(arr, expected, "{}", algorithm.name());
+        }
+    }
+
+    #[test]
+    fn config_checks_the_range() {
+        let args: Vec<String> = ["prog", "--size=10", "--min=-5", "--algorithm=all", "--seed=3"].iter().map(|s| s.to_string()).collect();
+        let config = Config::new(&args).unwrap();
+        assert_eq!((config.size, config.min, config.max, config.seed), (10, -5, DEFAULT_MAX, Some(3)));
+        assert_eq!(config.algorithms, Algorithm::ALL.to_vec());
+
+        let backwards: Vec<String> = ["prog", "--min=5", "--max=4"].iter().map(|s| s.to_string()).collect();
+        assert!(Config::new(&backwards).is_err());
+        let too_wide: Vec<String> = ["prog", "--min=0", "--max=9999999999"].iter().map(|s| s.to_string()).collect();
+        assert!(Config::new(&too_wide).is_err());
+    }
+
+    #[test]
+    fn checksums_ignore_order_but_not_values() {
+        assert_eq!(checksum(&[3, -1, 2]), checksum(&[-1, 2, 3]));
+        //same sum, different numbers
+        assert_ne!(checksum(&[1, 3]), checksum(&[2, 2]));
+        assert!(run(Config { size: 1000, min: -50, max: 50, algorithms: Algorithm::ALL.to_vec(), seed: Some(1) }).is_ok());
+    }
+}
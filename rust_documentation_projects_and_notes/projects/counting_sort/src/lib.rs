@@ -0,0 +1,315 @@
+/*
+ * counting sorts, shared by the projects that sort integers (array_sorting, how_to_write_tests)
+ *
+ * every sort works out the smallest and largest value itself and counts from the smallest, so any range of numbers
+ * can be sorted. ranges more than MAX_KEYS_PER_RECORD times wider than the number of records aren't counted one by
+ * one, that would take more memory and time than it saves (and the whole of u64 doesn't even fit in a usize count)
+ */
+use std::collections::HashMap;
+use std::hash::Hash;
+
+//the widest range counted, for every record being sorted, wider ranges are radix sorted or walked in sorted order
+pub const MAX_KEYS_PER_RECORD: usize = 16;
+
+//whether keys spanning max - min are close enough together to count every one of them
+fn is_countable(span: usize, len: usize) -> bool {
+    span < len.saturating_mul(MAX_KEYS_PER_RECORD)
+}
+
+/*
+ * integers counting sorts can sort, they only need to be told apart by how far they are from the smallest one
+ */
+pub trait Integer: Copy + Ord + Hash {
+    //how far self is above min, self must not be smaller than min
+    fn offset_from(self, min: Self) -> usize;
+    //the number offset above min
+    fn from_offset(min: Self, offset: usize) -> Self;
+}
+macro_rules! impl_integer {
+    ($($t:ty => $unsigned:ty),*) => {
+        $(impl Integer for $t {
+            fn offset_from(self, min: Self) -> usize {
+                //wrapping then reinterpreting as unsigned gives the distance even when it doesn't fit in the signed type
+                self.wrapping_sub(min) as $unsigned as usize
+            }
+            fn from_offset(min: Self, offset: usize) -> Self {
+                min.wrapping_add(offset as $t)
+            }
+        })*
+    };
+}
+impl_integer!(u8 => u8, u16 => u16, u32 => u32, u64 => u64, usize => usize, i8 => u8, i16 => u16, i32 => u32, i64 => u64, isize => usize);
+
+pub fn min_max<T: Integer>(arr: &[T]) -> Option<(T, T)> {
+    //DATA
+    let mut min = *arr.first()?;
+    let mut max = min;
+
+    //find mins and maxes
+    for &x in arr {
+        if x > max {
+            max = x;
+        } else if x < min {
+            min = x;
+        }
+    }
+
+    Some((min, max))
+}//min_max
+
+/*
+ * sorts arr by counting how many times each number appears in a hashmap, then writing every number from min to max
+ * back out that many times
+ *
+ * when the numbers are too spread out to walk every number from min to max, only the ones counted are walked, in order
+ */
+pub fn count_sort<T: Integer>(arr: &mut [T]) {
+    //DATA
+    let (min, max) = match min_max(arr) {
+        Some(range) => range,
+        None => return, //nothing to sort
+    };
+    let mut count_map = HashMap::new();
+    let mut j = 0; //used to index arr when rebuilding array
+
+    //build count hashmap
+    for x in arr.iter() {
+        *count_map.entry(*x).or_insert(0) += 1;//count occurances
+    }
+
+    //build sorted array
+    //averages out to O(2n)
+    let span = max.offset_from(min);
+    let values: Vec<T> = if is_countable(span, arr.len()) {
+        (0..=span).map(|offset| T::from_offset(min, offset)).filter(|value| count_map.contains_key(value)).collect()
+    } else {
+        let mut values: Vec<T> = count_map.keys().copied().collect();
+        values.sort_unstable();
+        values
+    };
+    for value in values {
+        let count = count_map[&value];
+        for slot in &mut arr[j..j + count] {
+            *slot = value;
+        }
+        j += count;
+    }
+}//count_sort
+
+/*
+ * sorts arr by counting how many times each number appears in an array indexed by how far the number is above min,
+ * then turning the counts into where each number ends up
+ */
+pub fn count_sort_vanilla<T: Integer>(arr: &mut [T]) {
+    //DATA
+    let min = match min_max(arr) {
+        Some((min, _)) => min,
+        None => return, //nothing to sort
+    };
+
+    //a number's key is how far it is above min, so the count array starts at min instead of 0
+    counting_sort_by_key(arr, |x| x.offset_from(min));
+}//count_sort_vanilla
+
+/*
+ * stably sorts records by a small integer key, records with the same key keep their order
+ *
+ * the count array has a slot for every key from the smallest to the largest, so keys that are too spread out to count
+ * are radix sorted instead
+ */
+pub fn counting_sort_by_key<T: Clone, F: Fn(&T) -> usize>(arr: &mut [T], key: F) {
+    //DATA
+    let (min, max) = match (arr.iter().map(&key).min(), arr.iter().map(&key).max()) {
+        (Some(min), Some(max)) => (min, max),
+        _ => return, //nothing to sort
+    };
+    if !is_countable(max - min, arr.len()) {
+        return radix_sort_by_key(arr, |x| (key(x) - min) as u64);
+    }
+    let mut count_arr = vec![0; max - min + 1];//stores counts of each key, the index is how far the key is above min, and the value of that index is the count
+    let mut tmp_arr = arr.to_vec();//used when building the sorted array before overwriting the base array
+
+    //count occurances of each key in arr
+    for x in arr.iter() {
+        count_arr[key(x) - min] += 1;
+    }
+
+    //store cumulative count, helps for unpacking later
+    for i in 1..count_arr.len() {
+        count_arr[i] += count_arr[i-1];
+    }
+
+    //build tmp_arr with count_arr, going backwards so the last record with a key gets the last spot for it (keeping it stable)
+    for x in arr.iter().rev() {
+        let count = &mut count_arr[key(x) - min];
+        *count -= 1;
+        tmp_arr[*count] = x.clone();
+    }
+
+    //rebuild orginal array, but sorted now
+    arr.clone_from_slice(&tmp_arr);
+}//counting_sort_by_key
+
+/*
+ * stably sorts records by an unsigned key (u8 to u64) with an LSD radix sort: one stable counting sort per byte of the
+ * key, least significant byte first, stopping after the most significant byte any key uses
+ *
+ * the records go back and forth between arr and one scratch copy, counted with the same 256 slot array every pass
+ */
+pub fn radix_sort_by_key<T: Clone, K: Into<u64>, F: Fn(&T) -> K>(arr: &mut [T], key: F) {
+    //DATA
+    let key = |x: &T| -> u64 { key(x).into() };
+    let max = match arr.iter().map(key).max() {
+        Some(max) => max,
+        None => return, //nothing to sort
+    };
+    let mut scratch = arr.to_vec();//the other side of every pass
+    let mut count_arr = [0usize; 256];//counts of each byte, then where the next record with that byte goes
+    let mut in_scratch = false;//whether the records sorted so far are in scratch rather than arr
+
+    //each pass keeps the order of the passes before it for records with the same byte
+    let mut shift = 0;
+    while shift < u64::BITS && max >> shift > 0 {
+        let (from, to): (&[T], &mut [T]) = if in_scratch {(&scratch, arr)} else {(arr, &mut scratch)};
+        let byte = |x: &T| (key(x) >> shift & 0xff) as usize;
+
+        //count occurances of each byte, then turn the counts into where the first record with each byte goes
+        count_arr.fill(0);
+        for x in from.iter() {
+            count_arr[byte(x)] += 1;
+        }
+        let mut position = 0;
+        for count in count_arr.iter_mut() {
+            let next = position + *count;
+            *count = position;
+            position = next;
+        }
+
+        //going forwards so records with the same byte keep their order
+        for x in from.iter() {
+            let position = &mut count_arr[byte(x)];
+            to[*position] = x.clone();
+            *position += 1;
+        }
+        in_scratch = !in_scratch;
+        shift += 8;
+    }
+
+    if in_scratch {
+        arr.clone_from_slice(&scratch);
+    }
+}//radix_sort_by_key
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    proptest! {
+        //numbers anywhere in i64, but close enough together to count
+        #[test]
+        fn counting_sorts_match_std(min in i64::MIN..=i64::MAX - 5000, offsets in prop::collection::vec(0i64..5000, 0..500)) {
+            let numbers: Vec<i64> = offsets.iter().map(|offset| min + offset).collect();
+            let mut expected = numbers.clone();
+            expected.sort();
+
+            let mut arr = numbers.clone();
+            count_sort(&mut arr);
+            prop_assert_eq!(&arr, &expected);
+            let mut arr = numbers.clone();
+            count_sort_vanilla(&mut arr);
+            prop_assert_eq!(&arr, &expected);
+            let mut arr = numbers.clone();
+            radix_sort_by_key(&mut arr, |x| x.offset_from(expected[0]) as u64);
+            prop_assert_eq!(&arr, &expected);
+        }
+
+        #[test]
+        fn sorts_by_key_match_std_stable_sort(records in prop::collection::vec((any::<u32>(), 0usize..300), 0..500)) {
+            let mut expected = records.clone();
+            expected.sort_by_key(|record| record.1);
+            let mut arr = records.clone();
+            counting_sort_by_key(&mut arr, |record| record.1);
+            prop_assert_eq!(&arr, &expected);
+
+            expected.sort_by_key(|record| record.0);
+            radix_sort_by_key(&mut arr, |record| record.0);
+            prop_assert_eq!(&arr, &expected);
+        }
+    }
+
+    proptest! {
+        //numbers as far apart as they can be, the range can't be counted or even fit in a usize + 1
+        #[test]
+        fn counting_sorts_handle_any_range(numbers in prop::collection::vec(prop_oneof![Just(i64::MIN), Just(i64::MAX), any::<i64>()], 0..300)) {
+            let mut expected = numbers.clone();
+            expected.sort();
+
+            let mut arr = numbers.clone();
+            count_sort(&mut arr);
+            prop_assert_eq!(&arr, &expected);
+            let mut arr = numbers.clone();
+            count_sort_vanilla(&mut arr);
+            prop_assert_eq!(&arr, &expected);
+            let mut arr: Vec<(u64, usize)> = numbers.iter().enumerate().map(|(i, x)| (*x as u64, i)).collect();
+            let mut expected = arr.clone();
+            expected.sort_by_key(|record| record.0);
+            counting_sort_by_key(&mut arr, |record| record.0 as usize);
+            prop_assert_eq!(&arr, &expected);
+        }
+    }
+
+    #[test]
+    fn counting_sorts_handle_extremes() {
+        let mut arr = [i8::MAX, i8::MIN, 0, -1, i8::MIN];
+        count_sort_vanilla(&mut arr);
+        assert_eq!(arr, [i8::MIN, i8::MIN, -1, 0, i8::MAX]);
+
+        let mut arr = [u8::MAX, 3, 0];
+        count_sort(&mut arr);
+        assert_eq!(arr, [0, 3, u8::MAX]);
+
+        let mut arr = [i64::MAX, i64::MIN, 0, i64::MIN];
+        count_sort(&mut arr);
+        assert_eq!(arr, [i64::MIN, i64::MIN, 0, i64::MAX]);
+        let mut arr = [usize::MAX, 0, usize::MAX, 1];
+        count_sort_vanilla(&mut arr);
+        assert_eq!(arr, [0, 1, usize::MAX, usize::MAX]);
+
+        let mut empty: [usize; 0] = [];
+        count_sort_vanilla(&mut empty);
+        count_sort(&mut empty);
+    }
+
+    #[test]
+    fn sorts_by_key_are_stable() {
+        //log records of (status code, hour, line), sorted by hour then status like a report would be
+        let mut rng = StdRng::seed_from_u64(11);
+        let statuses = [200u16, 201, 301, 404, 500];
+        let logs: Vec<(u16, u32, usize)> = (0..5_000).map(|line| (statuses[rng.gen_range(0..statuses.len())], rng.gen_range(0..24), line)).collect();
+
+        let mut expected = logs.clone();
+        expected.sort_by_key(|log| (log.1, log.0));
+        let mut arr = logs.clone();
+        counting_sort_by_key(&mut arr, |log| log.0 as usize);
+        counting_sort_by_key(&mut arr, |log| log.1 as usize);
+        assert_eq!(arr, expected);
+
+        //radix sorting the combined key in one go
+        let mut arr = logs.clone();
+        radix_sort_by_key(&mut arr, |log| (log.1 as u64) << 16 | log.0 as u64);
+        assert_eq!(arr, expected);
+
+        let wide: Vec<(u64, usize)> = (0..5_000).map(|i| (rng.gen(), i % 7)).collect();
+        let mut expected = wide.clone();
+        expected.sort_by_key(|record| record.1);
+        let mut arr = wide.clone();
+        radix_sort_by_key(&mut arr, |record| record.1 as u32);
+        assert_eq!(arr, expected);
+        expected.sort();
+        radix_sort_by_key(&mut arr, |record| record.0);
+        assert_eq!(arr, expected);
+    }
+}